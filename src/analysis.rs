@@ -0,0 +1,794 @@
+use serde_json::Value;
+
+use crate::model::{Analyzer, Component, Schema};
+use crate::resources::Resources;
+use crate::rules::{Finding, Severity};
+
+// analysis component attributes that name files in the configset, possibly comma-separated
+const RESOURCE_ATTRIBUTES: [&str; 8] = [
+    "synonyms",
+    "words",
+    "protected",
+    "dictionary",
+    "affix",
+    "types",
+    "mapping",
+    "articles",
+];
+
+// the Hunspell files, which declare their own encoding with SET in the affix file
+const ENCODED_RESOURCE_ATTRIBUTES: [&str; 2] = ["dictionary", "affix"];
+
+// components after which every token is lowercase
+const LOWERCASING_FACTORIES: [&str; 3] = [
+    "LowerCaseFilterFactory",
+    "LowerCaseTokenizerFactory",
+    "ICUFoldingFilterFactory",
+];
+
+/// Checks the files the analysis components refer to: each must exist, and all but the Hunspell
+/// files must have content and be UTF-8.
+pub fn check_analysis_resources(schema: &Schema, resources: &Resources) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    let mut checked: Vec<&str> = Vec::new();
+    let mut managed: Vec<String> = Vec::new();
+    for field_type in &schema.field_types {
+        for analyzer in &field_type.analyzers {
+            for (i, component) in analyzer.components.iter().enumerate() {
+                // several fieldTypes usually share one managed resource
+                if let Some((kind, file)) = managed_resource(component) {
+                    if !managed.contains(&file) {
+                        findings.extend(check_managed_resource(kind, &file, resources));
+                        managed.push(file);
+                    }
+                }
+                for attribute in RESOURCE_ATTRIBUTES {
+                    let Some(value) = component.element.attribute(attribute) else {
+                        continue;
+                    };
+                    for file in value
+                        .split(',')
+                        .map(str::trim)
+                        .filter(|file| !file.is_empty())
+                    {
+                        if checked.contains(&file) {
+                            continue;
+                        }
+                        checked.push(file);
+                        let reference = format!(
+                            "The file '{}' of {}=\"{}\" in the fieldType '{}'",
+                            file, attribute, value, field_type.name
+                        );
+                        let any_encoding = ENCODED_RESOURCE_ATTRIBUTES.contains(&attribute);
+                        if let Some(finding) =
+                            check_resource(resources, file, &reference, any_encoding)
+                        {
+                            findings.push(finding);
+                            continue;
+                        }
+                        let content = resources.read(file).unwrap_or_default();
+                        findings.extend(lint_resource(analyzer, i, attribute, file, &content));
+                    }
+                }
+            }
+        }
+    }
+    findings
+}
+
+fn check_resource(
+    resources: &Resources,
+    file: &str,
+    reference: &str,
+    any_encoding: bool,
+) -> Option<Finding> {
+    let finding = |severity: Severity, problem: String| Finding {
+        rule: "analysis-resource",
+        severity,
+        message: format!("{} {}.", reference, problem),
+        fixes: Vec::new(),
+    };
+    let Some(bytes) = resources.read_bytes(file) else {
+        return Some(finding(
+            Severity::Error,
+            "is not in the configset".to_string(),
+        ));
+    };
+    if any_encoding {
+        return None;
+    }
+    match std::str::from_utf8(&bytes) {
+        Err(e) => Some(finding(
+            Severity::Error,
+            format!("is not valid UTF-8 at byte {}", e.valid_up_to()),
+        )),
+        Ok(content) if content.trim_start_matches('\u{feff}').trim().is_empty() => {
+            Some(finding(Severity::Warning, "is empty".to_string()))
+        }
+        Ok(_) => None,
+    }
+}
+
+fn lint_resource(
+    analyzer: &Analyzer,
+    position: usize,
+    attribute: &str,
+    file: &str,
+    content: &str,
+) -> Vec<Finding> {
+    let component = &analyzer.components[position];
+    let ignore_case = component.element.attribute("ignoreCase") == Some("true");
+    let format = component.element.attribute("format");
+    if attribute == "synonyms"
+        && is_synonym_filter(component)
+        && format.is_none_or(|format| format == "solr")
+    {
+        return lint_synonyms(file, content, ignore_case);
+    }
+    if attribute == "words" && component.is("StopFilterFactory") {
+        let lowercasing = |component: &Component| {
+            LOWERCASING_FACTORIES
+                .iter()
+                .any(|factory| component.is(factory))
+        };
+        let lowercased = analyzer.components[..position].iter().any(lowercasing);
+        // check_analyzer_chains already reports a stop filter that comes before the lowercasing
+        let lowercased_after = analyzer.components[position + 1..].iter().any(lowercasing);
+        return lint_stopwords(
+            file,
+            content,
+            ignore_case,
+            lowercased,
+            lowercased_after,
+            format == Some("snowball"),
+        );
+    }
+    Vec::new()
+}
+
+fn lint(severity: Severity, rule: &'static str, message: String) -> Finding {
+    Finding {
+        rule,
+        severity,
+        message,
+        fixes: Vec::new(),
+    }
+}
+
+// splits at separators that aren't escaped with a backslash; the parts are unescaped if asked to
+fn split_unescaped(text: &str, separator: &str, unescape: bool) -> Vec<String> {
+    let mut parts = vec![String::new()];
+    let mut rest = text;
+    while let Some(c) = rest.chars().next() {
+        let part = parts.last_mut().expect("there is always a part");
+        if c == '\\' {
+            let escaped = rest[1..].chars().next();
+            if !unescape || escaped.is_none() {
+                part.push(c);
+            }
+            if let Some(escaped) = escaped {
+                part.push(escaped);
+                rest = &rest[1 + escaped.len_utf8()..];
+            } else {
+                rest = &rest[1..];
+            }
+        } else if rest.starts_with(separator) {
+            parts.push(String::new());
+            rest = &rest[separator.len()..];
+        } else {
+            part.push(c);
+            rest = &rest[c.len_utf8()..];
+        }
+    }
+    parts
+}
+
+/// Lints a synonyms file in the Solr format: `a, b` lines make the terms equivalent and
+/// `a, b => c` lines map the terms on the left to those on the right.
+fn lint_synonyms(file: &str, content: &str, ignore_case: bool) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    let mut seen: Vec<(Vec<String>, Vec<String>, usize)> = Vec::new();
+    for (i, line) in content.lines().enumerate() {
+        let number = i + 1;
+        let line = line.trim_start_matches('\u{feff}').trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let sides = split_unescaped(line, "=>", false);
+        if sides.len() > 2 {
+            findings.push(lint(
+                Severity::Error,
+                "synonyms",
+                format!("{}:{}: The line has more than one '=>'.", file, number),
+            ));
+            continue;
+        }
+        let sides: Vec<Vec<String>> = sides
+            .iter()
+            .map(|side| {
+                split_unescaped(side, ",", true)
+                    .iter()
+                    .map(|term| match ignore_case {
+                        true => term.trim().to_lowercase(),
+                        false => term.trim().to_string(),
+                    })
+                    .collect()
+            })
+            .collect();
+        if sides.iter().flatten().any(String::is_empty) {
+            findings.push(lint(
+                Severity::Error,
+                "synonyms",
+                format!("{}:{}: The line has an empty synonym.", file, number),
+            ));
+            continue;
+        }
+        let normalized = |terms: &[String]| {
+            let mut terms = terms.to_vec();
+            terms.sort();
+            terms.dedup();
+            terms
+        };
+        let left = normalized(&sides[0]);
+        let right = sides
+            .get(1)
+            .map(|side| normalized(side))
+            .unwrap_or_default();
+        if sides.len() == 2 && left == right {
+            findings.push(lint(
+                Severity::Warning,
+                "synonyms",
+                format!(
+                    "{}:{}: The line maps '{}' to itself.",
+                    file,
+                    number,
+                    left.join(", ")
+                ),
+            ));
+        } else if sides.len() == 1 && left.len() < 2 {
+            findings.push(lint(
+                Severity::Warning,
+                "synonyms",
+                format!(
+                    "{}:{}: The line has no synonym for '{}' but itself.",
+                    file, number, left[0]
+                ),
+            ));
+        }
+        match seen.iter().find(|(l, r, _)| *l == left && *r == right) {
+            Some((_, _, first)) => findings.push(lint(
+                Severity::Warning,
+                "synonyms",
+                format!("{}:{}: The line duplicates line {}.", file, number, first),
+            )),
+            None => seen.push((left, right, number)),
+        }
+    }
+    findings
+}
+
+/// Lints a stopwords file with one word per line, or several per line in the snowball format.
+fn lint_stopwords(
+    file: &str,
+    content: &str,
+    ignore_case: bool,
+    lowercased: bool,
+    lowercased_after: bool,
+    snowball: bool,
+) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    let mut seen: Vec<(String, usize)> = Vec::new();
+    for (i, line) in content.lines().enumerate() {
+        let number = i + 1;
+        let line = line.trim_start_matches('\u{feff}');
+        let words: Vec<&str> = if snowball {
+            line.split('|')
+                .next()
+                .unwrap_or_default()
+                .split_whitespace()
+                .collect()
+        } else {
+            if line.trim().is_empty() || line.trim_start().starts_with('#') {
+                continue;
+            }
+            if line.trim() != line {
+                findings.push(lint(
+                    Severity::Warning,
+                    "stopwords",
+                    format!(
+                        "{}:{}: '{}' has leading or trailing whitespace.",
+                        file,
+                        number,
+                        line.trim()
+                    ),
+                ));
+            }
+            vec![line.trim()]
+        };
+        for word in words {
+            let has_uppercase = word.chars().any(char::is_uppercase);
+            if has_uppercase && lowercased && !ignore_case {
+                findings.push(lint(
+                    Severity::Warning,
+                    "stopwords",
+                    format!(
+                        "{}:{}: '{}' never matches, since the tokens are lowercased before the stop filter.",
+                        file, number, word
+                    ),
+                ));
+            } else if has_uppercase && !lowercased && !lowercased_after && !ignore_case {
+                findings.push(lint(
+                    Severity::Warning,
+                    "stopwords",
+                    format!(
+                        "{}:{}: '{}' only matches tokens of the same case, since the tokens are not lowercased and ignoreCase is not \"true\".",
+                        file, number, word
+                    ),
+                ));
+            }
+            let key = match ignore_case {
+                true => word.to_lowercase(),
+                false => word.to_string(),
+            };
+            match seen.iter().find(|(seen, _)| *seen == key) {
+                Some((_, first)) => findings.push(lint(
+                    Severity::Warning,
+                    "stopwords",
+                    format!("{}:{}: '{}' duplicates line {}.", file, number, word, first),
+                )),
+                None => seen.push((key, number)),
+            }
+        }
+    }
+    findings
+}
+
+// filters that produce token graphs, which the index can't store without flattening
+const GRAPH_FILTERS: [&str; 2] = [
+    "SynonymGraphFilterFactory",
+    "WordDelimiterGraphFilterFactory",
+];
+
+// deprecated filters and the graph filters that replace them
+const DEPRECATED_FILTERS: [(&str, &str); 2] = [
+    ("SynonymFilterFactory", "SynonymGraphFilterFactory"),
+    (
+        "WordDelimiterFilterFactory",
+        "WordDelimiterGraphFilterFactory",
+    ),
+];
+
+const FLATTEN_GRAPH_FILTER: &str = "FlattenGraphFilterFactory";
+
+fn is_stemmer(component: &Component) -> bool {
+    let factory = component.factory().to_ascii_lowercase();
+    factory.ends_with("stemfilterfactory") || factory == "snowballporterfilterfactory"
+}
+
+fn is_synonym_filter(component: &Component) -> bool {
+    component.is("SynonymGraphFilterFactory") || component.is("SynonymFilterFactory")
+}
+
+/// Checks the order of the components in every analyzer chain against constraints
+/// that Solr doesn't enforce but that break matching.
+pub fn check_analyzer_chains(schema: &Schema) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    for field_type in &schema.field_types {
+        for analyzer in &field_type.analyzers {
+            let kind = analyzer.kind.as_deref().unwrap_or("untyped");
+            let mut report = |message: String| {
+                findings.push(lint(
+                    Severity::Warning,
+                    "analyzer-order",
+                    format!(
+                        "The {} analyzer of the fieldType '{}': {}",
+                        kind, field_type.name, message
+                    ),
+                ))
+            };
+            let components = &analyzer.components;
+            for (i, component) in components.iter().enumerate() {
+                let after = &components[i + 1..];
+                let before = &components[..i];
+                if let Some((deprecated, replacement)) = DEPRECATED_FILTERS
+                    .iter()
+                    .find(|(deprecated, _)| component.is(deprecated))
+                {
+                    report(format!(
+                        "{} is deprecated since it can't handle multi-word tokens; use {} instead.",
+                        deprecated, replacement
+                    ));
+                }
+                if let Some(graph) = GRAPH_FILTERS.iter().find(|graph| component.is(graph)) {
+                    let flattened = after.iter().any(|next| next.is(FLATTEN_GRAPH_FILTER));
+                    match kind {
+                        "index" if !flattened => report(format!(
+                            "{} produces a token graph that the index can't store; add a {} after it.",
+                            graph, FLATTEN_GRAPH_FILTER
+                        )),
+                        "untyped" => report(format!(
+                            "{} is used at index and query time, but a {} is only needed at index time; split the analyzer into an index and a query analyzer.",
+                            graph, FLATTEN_GRAPH_FILTER
+                        )),
+                        _ => {}
+                    }
+                }
+                if component.is(FLATTEN_GRAPH_FILTER) && kind == "query" {
+                    report(format!(
+                        "{} breaks the token graph that phrase queries need; only use it at index time.",
+                        FLATTEN_GRAPH_FILTER
+                    ));
+                }
+                let lowercased_after = after
+                    .iter()
+                    .any(|next| LOWERCASING_FACTORIES.iter().any(|factory| next.is(factory)));
+                if component.is("StopFilterFactory")
+                    && component.element.attribute("ignoreCase") != Some("true")
+                    && lowercased_after
+                {
+                    report(
+                        "StopFilterFactory comes before the tokens are lowercased, so capitalized stopwords are kept; lowercase first or set ignoreCase=\"true\"."
+                            .to_string(),
+                    );
+                }
+                if is_synonym_filter(component) {
+                    if let Some(stemmer) = before.iter().find(|previous| is_stemmer(previous)) {
+                        report(format!(
+                            "{} stems the tokens before {}, so synonyms only match their stems; stem after the synonyms.",
+                            stemmer.factory(),
+                            component.factory()
+                        ));
+                    }
+                }
+            }
+        }
+    }
+    findings
+}
+
+// managed resources are kept in JSON files that Solr creates when they are first used
+fn managed_resource(component: &Component) -> Option<(&'static str, String)> {
+    let managed = component.element.attribute("managed")?;
+    let synonyms = component.is("ManagedSynonymGraphFilterFactory")
+        || component.is("ManagedSynonymFilterFactory");
+    let kind = match (synonyms, component.is("ManagedStopFilterFactory")) {
+        (true, _) => "synonyms",
+        (_, true) => "stopwords",
+        _ => return None,
+    };
+    Some((kind, format!("_schema_analysis_{}_{}.json", kind, managed)))
+}
+
+fn check_managed_resource(kind: &'static str, file: &str, resources: &Resources) -> Vec<Finding> {
+    let Some(content) = resources.read(file) else {
+        return Vec::new();
+    };
+    let error = |message: &str| {
+        vec![lint(
+            Severity::Error,
+            kind,
+            format!("{}: {}", file, message),
+        )]
+    };
+    let Ok(json) = serde_json::from_str::<Value>(&content) else {
+        return error("The managed resource is not valid JSON.");
+    };
+    let ignore_case = json["initArgs"]["ignoreCase"].as_bool().unwrap_or(false);
+    let normalize = |term: &str| match ignore_case {
+        true => term.to_lowercase(),
+        false => term.to_string(),
+    };
+    let mut findings = Vec::new();
+    if kind == "synonyms" {
+        let Some(map) = json["managedMap"].as_object() else {
+            return error("The managed resource has no 'managedMap' object.");
+        };
+        let mut keys: Vec<String> = Vec::new();
+        for (term, mapped) in map {
+            let Some(mapped) = mapped.as_array().and_then(|mapped| {
+                mapped
+                    .iter()
+                    .map(Value::as_str)
+                    .collect::<Option<Vec<&str>>>()
+            }) else {
+                findings.extend(error(&format!(
+                    "The synonyms of '{}' are not a list of strings.",
+                    term
+                )));
+                continue;
+            };
+            if keys.contains(&normalize(term)) {
+                findings.push(lint(
+                    Severity::Warning,
+                    kind,
+                    format!("{}: '{}' is mapped more than once.", file, term),
+                ));
+            }
+            keys.push(normalize(term));
+            if mapped
+                .iter()
+                .all(|synonym| normalize(synonym) == normalize(term))
+            {
+                findings.push(lint(
+                    Severity::Warning,
+                    kind,
+                    format!("{}: '{}' is mapped to itself only.", file, term),
+                ));
+            }
+        }
+    } else {
+        let Some(words) = json["managedList"].as_array().and_then(|words| {
+            words
+                .iter()
+                .map(Value::as_str)
+                .collect::<Option<Vec<&str>>>()
+        }) else {
+            return error("The managed resource has no 'managedList' of strings.");
+        };
+        let mut seen: Vec<String> = Vec::new();
+        for word in words {
+            if word.trim() != word {
+                findings.push(lint(
+                    Severity::Warning,
+                    kind,
+                    format!(
+                        "{}: '{}' has leading or trailing whitespace.",
+                        file,
+                        word.trim()
+                    ),
+                ));
+            }
+            if seen.contains(&normalize(word)) {
+                findings.push(lint(
+                    Severity::Warning,
+                    kind,
+                    format!("{}: '{}' is listed more than once.", file, word),
+                ));
+            }
+            seen.push(normalize(word));
+        }
+    }
+    findings
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::analysis::{check_analysis_resources, check_analyzer_chains};
+    use crate::model::Schema;
+    use crate::resources::Resources;
+    use crate::tree::parse_document;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_analysis_resources() {
+        let example = r#"<schema version="1.6">
+  <fieldType name="text_en" class="solr.TextField">
+    <analyzer>
+      <tokenizer class="solr.StandardTokenizerFactory" />
+      <filter class="solr.StopFilterFactory" words="lang/stopwords_en.txt, stopwords.txt" ignoreCase="true" />
+      <filter class="solr.SynonymGraphFilterFactory" synonyms="synonyms.txt" />
+      <filter class="solr.KeywordMarkerFilterFactory" protected="protwords.txt" />
+      <filter class="solr.HunspellStemFilterFactory" dictionary="en_GB.dic" affix="en_GB.aff" />
+      <filter class="solr.HunspellStemFilterFactory" dictionary="de_DE.dic" affix="de_DE.aff" />
+    </analyzer>
+  </fieldType>
+  <fieldType name="text_general" class="solr.TextField">
+    <analyzer>
+      <tokenizer class="solr.StandardTokenizerFactory" />
+      <filter class="solr.StopFilterFactory" words="stopwords.txt" />
+    </analyzer>
+  </fieldType>
+</schema>"#;
+        let schema = Schema::from_document(&parse_document(Cursor::new(example)).unwrap());
+        let resources = Resources::default()
+            .with_file("lang/stopwords_en.txt", "a\nan\n")
+            .with_file(
+                "stopwords.txt",
+                "# Licensed to the Apache Software Foundation\n",
+            )
+            .with_file("synonyms.txt", "  \n")
+            .with_file("en_GB.dic", b"1\nfa\xe7ade\n")
+            .with_file("en_GB.aff", "SET ISO8859-1\n")
+            .with_file("de_DE.dic", "1\nStra\u{df}e\n");
+        let findings: Vec<String> = check_analysis_resources(&schema, &resources)
+            .into_iter()
+            .map(|finding| finding.to_string())
+            .collect();
+        assert_eq!(
+            findings,
+            vec![
+                "warning[analysis-resource]: The file 'synonyms.txt' of synonyms=\"synonyms.txt\" in the fieldType 'text_en' is empty.",
+                "error[analysis-resource]: The file 'protwords.txt' of protected=\"protwords.txt\" in the fieldType 'text_en' is not in the configset.",
+                "error[analysis-resource]: The file 'de_DE.aff' of affix=\"de_DE.aff\" in the fieldType 'text_en' is not in the configset.",
+            ]
+        );
+    }
+
+    fn lint(schema: &str, resources: &Resources) -> Vec<String> {
+        let schema = Schema::from_document(&parse_document(Cursor::new(schema)).unwrap());
+        check_analysis_resources(&schema, resources)
+            .into_iter()
+            .map(|finding| finding.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_synonyms_file() {
+        let example = r#"<schema version="1.6">
+  <fieldType name="text" class="solr.TextField">
+    <analyzer>
+      <tokenizer class="solr.StandardTokenizerFactory" />
+      <filter class="solr.SynonymGraphFilterFactory" synonyms="synonyms.txt" ignoreCase="true" />
+    </analyzer>
+  </fieldType>
+</schema>"#;
+        let synonyms = "# comment\n\
+            GB, gib, gigabyte\n\
+            i-pod, i pod => ipod\n\
+            a\\,b, ab\n\
+            tv => tv\n\
+            usa => united states => america\n\
+            dog, , hound\n\
+            universe\n\
+            gigabyte, gb, GiB\n";
+        let resources = Resources::default().with_file("synonyms.txt", synonyms);
+        assert_eq!(
+            lint(example, &resources),
+            vec![
+                "warning[synonyms]: synonyms.txt:5: The line maps 'tv' to itself.",
+                "error[synonyms]: synonyms.txt:6: The line has more than one '=>'.",
+                "error[synonyms]: synonyms.txt:7: The line has an empty synonym.",
+                "warning[synonyms]: synonyms.txt:8: The line has no synonym for 'universe' but itself.",
+                "warning[synonyms]: synonyms.txt:9: The line duplicates line 2.",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_stopwords_file() {
+        let example = r#"<schema version="1.6">
+  <fieldType name="text_lower" class="solr.TextField">
+    <analyzer>
+      <tokenizer class="solr.StandardTokenizerFactory" />
+      <filter name="lowercase" />
+      <filter class="solr.StopFilterFactory" words="stopwords.txt" />
+    </analyzer>
+  </fieldType>
+  <fieldType name="text_exact" class="solr.TextField">
+    <analyzer>
+      <tokenizer class="solr.StandardTokenizerFactory" />
+      <filter class="solr.StopFilterFactory" words="lang/stopwords_de.txt" format="snowball" />
+    </analyzer>
+  </fieldType>
+  <fieldType name="text_late" class="solr.TextField">
+    <analyzer>
+      <tokenizer class="solr.StandardTokenizerFactory" />
+      <filter class="solr.StopFilterFactory" words="lang/stopwords_fr.txt" />
+      <filter name="lowercase" />
+    </analyzer>
+  </fieldType>
+</schema>"#;
+        let resources = Resources::default()
+            .with_file("stopwords.txt", "a\nThe\nan \na\n")
+            .with_file("lang/stopwords_de.txt", "aber | but\nAlle allem\n\naber\n")
+            .with_file("lang/stopwords_fr.txt", "Le\nla\n");
+        assert_eq!(
+            lint(example, &resources),
+            vec![
+                "warning[stopwords]: stopwords.txt:2: 'The' never matches, since the tokens are lowercased before the stop filter.",
+                "warning[stopwords]: stopwords.txt:3: 'an' has leading or trailing whitespace.",
+                "warning[stopwords]: stopwords.txt:4: 'a' duplicates line 1.",
+                "warning[stopwords]: lang/stopwords_de.txt:2: 'Alle' only matches tokens of the same case, since the tokens are not lowercased and ignoreCase is not \"true\".",
+                "warning[stopwords]: lang/stopwords_de.txt:4: 'aber' duplicates line 1.",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_managed_resources() {
+        let example = r#"<schema version="1.6">
+  <fieldType name="managed_en" class="solr.TextField">
+    <analyzer>
+      <tokenizer class="solr.StandardTokenizerFactory" />
+      <filter class="solr.ManagedStopFilterFactory" managed="english" />
+      <filter class="solr.ManagedSynonymGraphFilterFactory" managed="english" />
+      <filter class="solr.ManagedSynonymGraphFilterFactory" managed="german" />
+      <filter class="solr.ManagedStopFilterFactory" managed="french" />
+    </analyzer>
+  </fieldType>
+  <fieldType name="managed_en_exact" class="solr.TextField">
+    <analyzer>
+      <tokenizer class="solr.StandardTokenizerFactory" />
+      <filter class="solr.ManagedStopFilterFactory" managed="english" />
+    </analyzer>
+  </fieldType>
+</schema>"#;
+        let resources = Resources::default()
+            .with_file(
+                "_schema_analysis_stopwords_english.json",
+                r#"{"initArgs": {"ignoreCase": true}, "managedList": ["a", "The", "the ", "an", "A"]}"#,
+            )
+            .with_file(
+                "_schema_analysis_synonyms_english.json",
+                r#"{"initArgs": {"ignoreCase": true}, "managedMap": {"tv": ["TV"], "happy": ["glad", "joyful"], "mb": "megabyte"}}"#,
+            )
+            .with_file("_schema_analysis_synonyms_german.json", "{");
+        assert_eq!(
+            lint(example, &resources),
+            vec![
+                "warning[stopwords]: _schema_analysis_stopwords_english.json: 'the' has leading or trailing whitespace.",
+                "warning[stopwords]: _schema_analysis_stopwords_english.json: 'A' is listed more than once.",
+                "error[synonyms]: _schema_analysis_synonyms_english.json: The synonyms of 'mb' are not a list of strings.",
+                "warning[synonyms]: _schema_analysis_synonyms_english.json: 'tv' is mapped to itself only.",
+                "error[synonyms]: _schema_analysis_synonyms_german.json: The managed resource is not valid JSON.",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_analyzer_chains() {
+        let example = r#"<schema version="1.6">
+  <fieldType name="text_en" class="solr.TextField">
+    <analyzer type="index">
+      <tokenizer class="solr.StandardTokenizerFactory" />
+      <filter class="solr.WordDelimiterGraphFilterFactory" />
+      <filter class="solr.StopFilterFactory" words="stopwords.txt" />
+      <filter name="lowercase" />
+      <filter name="porterStem" />
+      <filter class="solr.SynonymGraphFilterFactory" synonyms="synonyms.txt" />
+      <filter class="solr.FlattenGraphFilterFactory" />
+    </analyzer>
+    <analyzer type="query">
+      <tokenizer class="solr.StandardTokenizerFactory" />
+      <filter class="solr.SynonymFilterFactory" synonyms="synonyms.txt" />
+      <filter class="solr.FlattenGraphFilterFactory" />
+    </analyzer>
+  </fieldType>
+  <fieldType name="text_general" class="solr.TextField">
+    <analyzer>
+      <tokenizer class="solr.StandardTokenizerFactory" />
+      <filter class="solr.LowerCaseFilterFactory" />
+      <filter class="solr.SynonymGraphFilterFactory" synonyms="synonyms.txt" />
+    </analyzer>
+  </fieldType>
+</schema>"#;
+        let schema = Schema::from_document(&parse_document(Cursor::new(example)).unwrap());
+        let findings: Vec<String> = check_analyzer_chains(&schema)
+            .into_iter()
+            .map(|finding| finding.to_string())
+            .collect();
+        assert_eq!(
+            findings,
+            vec![
+                "warning[analyzer-order]: The index analyzer of the fieldType 'text_en': StopFilterFactory comes before the tokens are lowercased, so capitalized stopwords are kept; lowercase first or set ignoreCase=\"true\".",
+                "warning[analyzer-order]: The index analyzer of the fieldType 'text_en': PorterStemFilterFactory stems the tokens before SynonymGraphFilterFactory, so synonyms only match their stems; stem after the synonyms.",
+                "warning[analyzer-order]: The query analyzer of the fieldType 'text_en': SynonymFilterFactory is deprecated since it can't handle multi-word tokens; use SynonymGraphFilterFactory instead.",
+                "warning[analyzer-order]: The query analyzer of the fieldType 'text_en': FlattenGraphFilterFactory breaks the token graph that phrase queries need; only use it at index time.",
+                "warning[analyzer-order]: The untyped analyzer of the fieldType 'text_general': SynonymGraphFilterFactory is used at index and query time, but a FlattenGraphFilterFactory is only needed at index time; split the analyzer into an index and a query analyzer.",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_graph_filter_needs_flattening_at_index_time() {
+        let example = r#"<schema version="1.6">
+  <fieldType name="text" class="solr.TextField">
+    <analyzer type="index">
+      <tokenizer class="solr.StandardTokenizerFactory" />
+      <filter class="solr.SynonymGraphFilterFactory" synonyms="synonyms.txt" />
+    </analyzer>
+    <analyzer type="query">
+      <tokenizer class="solr.StandardTokenizerFactory" />
+      <filter class="solr.SynonymGraphFilterFactory" synonyms="synonyms.txt" />
+    </analyzer>
+  </fieldType>
+</schema>"#;
+        let schema = Schema::from_document(&parse_document(Cursor::new(example)).unwrap());
+        let findings = check_analyzer_chains(&schema);
+        assert_eq!(findings.len(), 1);
+        assert_eq!(
+            findings[0].message,
+            "The index analyzer of the fieldType 'text': SynonymGraphFilterFactory produces a token graph that the index can't store; add a FlattenGraphFilterFactory after it."
+        );
+    }
+}
@@ -0,0 +1,201 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::io::{self, BufRead};
+
+use crate::model::Schema;
+use crate::solrconfig::{field_references, missing_capability};
+
+// the parameters whose field references are resolved
+const AUDITED_PARAMETERS: [&str; 6] = ["q", "fq", "fl", "sort", "facet.field", "qf"];
+
+/// The parameters of a request log line, e.g.
+/// `... path=/select params={q=title:solr&sort=price+asc} hits=3 status=0 QTime=1`.
+pub fn request_parameters(line: &str) -> Option<Vec<(String, String)>> {
+    let start = line.find("params={")? + "params={".len();
+    let end = start + line[start..].rfind('}')?;
+    Some(
+        line[start..end]
+            .split('&')
+            .filter(|pair| !pair.is_empty())
+            .map(|pair| {
+                let (name, value) = pair.split_once('=').unwrap_or((pair, ""));
+                (percent_decode(name), percent_decode(value))
+            })
+            .collect(),
+    )
+}
+
+fn percent_decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'+' => decoded.push(b' '),
+            b'%' if i + 2 < bytes.len() => {
+                let hex = std::str::from_utf8(&bytes[i + 1..i + 3]).unwrap_or_default();
+                match u8::from_str_radix(hex, 16) {
+                    Ok(byte) => {
+                        decoded.push(byte);
+                        i += 2;
+                    }
+                    Err(_) => decoded.push(b'%'),
+                }
+            }
+            byte => decoded.push(byte),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+struct Problem {
+    requests: usize,
+    parameters: BTreeSet<String>,
+}
+
+/// The problems of the field references of the audited requests, by field and message.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct QueryAudit {
+    pub requests: usize,
+    pub failing_requests: usize,
+    problems: BTreeMap<(String, String), Problem>,
+}
+
+impl QueryAudit {
+    /// Resolves the fields a request refers to and counts their problems once per request.
+    pub fn add_request(&mut self, schema: &Schema, parameters: &[(String, String)]) {
+        let mut found: BTreeMap<(String, String), BTreeSet<String>> = BTreeMap::new();
+        for (parameter, value) in parameters {
+            if !AUDITED_PARAMETERS.contains(&parameter.as_str()) {
+                continue;
+            }
+            for (name, field_use) in field_references(parameter, value) {
+                let message = match schema.resolve_field(&name) {
+                    None => "not defined in the schema".to_string(),
+                    Some(field) => match missing_capability(schema, field, field_use) {
+                        Some(missing) => format!("needs {}", missing),
+                        None => continue,
+                    },
+                };
+                found
+                    .entry((name, message))
+                    .or_default()
+                    .insert(parameter.clone());
+            }
+        }
+        self.requests += 1;
+        if !found.is_empty() {
+            self.failing_requests += 1;
+        }
+        for (key, parameters) in found {
+            let problem = self.problems.entry(key).or_default();
+            problem.requests += 1;
+            problem.parameters.extend(parameters);
+        }
+    }
+
+    /// Audits every request line of a log; other lines are skipped.
+    pub fn add_log(&mut self, schema: &Schema, mut log: impl BufRead) -> io::Result<()> {
+        let mut line = Vec::new();
+        while log.read_until(b'\n', &mut line)? > 0 {
+            if let Some(parameters) = request_parameters(&String::from_utf8_lossy(&line)) {
+                self.add_request(schema, &parameters);
+            }
+            line.clear();
+        }
+        Ok(())
+    }
+
+    pub fn has_problems(&self) -> bool {
+        !self.problems.is_empty()
+    }
+}
+
+impl fmt::Display for QueryAudit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Audited {} requests, {} with problems",
+            self.requests, self.failing_requests
+        )?;
+        let mut problems: Vec<_> = self.problems.iter().collect();
+        // the most frequent first, then by field
+        problems.sort_by(|(a_key, a), (b_key, b)| {
+            b.requests.cmp(&a.requests).then_with(|| a_key.cmp(b_key))
+        });
+        let width = problems
+            .first()
+            .map(|(_, problem)| problem.requests.to_string().len())
+            .unwrap_or_default();
+        for ((field, message), problem) in problems {
+            let parameters: Vec<&str> = problem.parameters.iter().map(String::as_str).collect();
+            writeln!(
+                f,
+                "  {:>width$}  {}: {} ({})",
+                problem.requests,
+                field,
+                message,
+                parameters.join(", "),
+                width = width
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::audit::{request_parameters, QueryAudit};
+    use crate::model::Schema;
+    use crate::tree::parse_document;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_request_parameters() {
+        assert_eq!(
+            request_parameters(
+                "2024-05-02 10:00:00.123 INFO  (qtp-1) [c:books] o.a.s.c.S.Request webapp=/solr path=/select params={q=title:%22rust+book%22&fq={!tag%3Dt}type:book} hits=3 status=0 QTime=1"
+            ),
+            Some(vec![
+                ("q".to_string(), "title:\"rust book\"".to_string()),
+                ("fq".to_string(), "{!tag=t}type:book".to_string()),
+            ])
+        );
+        assert_eq!(request_parameters("2024-05-02 INFO Registered core"), None);
+    }
+
+    #[test]
+    fn test_audit() {
+        let schema = Schema::from_document(
+            &parse_document(Cursor::new(
+                r#"<schema version="1.6">
+  <field name="id" type="string" />
+  <field name="title" type="text" />
+  <field name="price" type="pfloat" />
+  <fieldType name="string" class="solr.StrField" />
+  <fieldType name="text" class="solr.TextField" uninvertible="false" />
+  <fieldType name="pfloat" class="solr.FloatPointField" docValues="true" />
+</schema>"#,
+            ))
+            .unwrap(),
+        );
+        let log = "\
+INFO webapp=/solr path=/select params={q=title:rust&sort=title+asc&fl=id,colour} hits=1
+INFO webapp=/solr path=/select params={q=colour:red&fq=colour:blue&facet.field=price} hits=0
+INFO Closing searcher
+INFO webapp=/solr path=/select params={q=*:*&sort=price+desc} hits=9
+";
+        let mut audit = QueryAudit::default();
+        audit.add_log(&schema, Cursor::new(log)).unwrap();
+        assert!(audit.has_problems());
+        assert_eq!(
+            audit.to_string(),
+            "Audited 3 requests, 2 with problems
+  2  colour: not defined in the schema (fl, fq, q)
+  1  title: needs docValues=\"true\", or indexed=\"true\" and uninvertible=\"true\" (sort)
+"
+        );
+    }
+}
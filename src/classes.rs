@@ -0,0 +1,706 @@
+use crate::config::Config;
+use crate::model::{matches_pattern, FieldType, Schema};
+use crate::resources::{enum_values, Resources};
+use crate::rules::{remove_attribute, Finding, Severity};
+use crate::schema::{
+    DEPRECATED_FIELD_TYPES, FIELD_TYPE_CLASSES, FIELD_TYPE_GENERAL_PROPERTIES,
+    OPTIONAL_FIELD_PROPERTIES,
+};
+
+// the classes that analyze their values and so accept analyzers and the text properties
+const ANALYZABLE_CLASSES: [&str; 3] = ["TextField", "SortableTextField", "PreAnalyzedField"];
+
+// the classes that analyze wildcard, prefix and range query terms with a multiterm analyzer
+const MULTITERM_CLASSES: [&str; 3] = ["TextField", "SortableTextField", "PreAnalyzedField"];
+
+// fieldType properties that only change how the tokens of text are indexed or queried;
+// positionIncrementGap is not one of them, since it separates the values of any multiValued field
+const TEXT_PROPERTIES: [&str; 3] = [
+    "autoGeneratePhraseQueries",
+    "enableGraphQueries",
+    "synonymQueryStyle",
+];
+
+const DISTANCE_UNITS: &[&str] = &["kilometers", "miles", "degrees"];
+
+/// The values a class specific fieldType attribute accepts.
+#[derive(Debug, Clone, Copy)]
+pub enum AttributeValue {
+    Bool,
+    Integer(i64, i64),
+    Decimal(f64, f64),
+    OneOf(&'static [&'static str]),
+    /// The name of another fieldType in the schema.
+    FieldType,
+    /// An ISO 4217 code such as `USD`.
+    Currency,
+    Text,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct ClassAttribute {
+    pub name: &'static str,
+    pub value: AttributeValue,
+    pub required: bool,
+}
+
+const fn optional(name: &'static str, value: AttributeValue) -> ClassAttribute {
+    ClassAttribute {
+        name,
+        value,
+        required: false,
+    }
+}
+
+const fn required(name: &'static str, value: AttributeValue) -> ClassAttribute {
+    ClassAttribute {
+        name,
+        value,
+        required: true,
+    }
+}
+
+// the attributes shared by every spatial class, read by AbstractSpatialFieldType
+const SPATIAL_ATTRIBUTES: [ClassAttribute; 3] = [
+    optional("geo", AttributeValue::Bool),
+    optional("distanceUnits", AttributeValue::OneOf(DISTANCE_UNITS)),
+    optional("spatialContextFactory", AttributeValue::Text),
+];
+
+const PREFIX_TREE_ATTRIBUTES: [ClassAttribute; 12] = [
+    optional("maxDistErr", AttributeValue::Decimal(0.0, f64::MAX)),
+    optional("distErrPct", AttributeValue::Decimal(0.0, 0.5)),
+    optional(
+        "prefixTree",
+        AttributeValue::OneOf(&["geohash", "quad", "packedQuad", "s2"]),
+    ),
+    optional("maxLevels", AttributeValue::Integer(1, i64::MAX)),
+    optional("worldBounds", AttributeValue::Text),
+    optional("format", AttributeValue::OneOf(&["WKT", "GeoJSON"])),
+    optional("normWrapLongitude", AttributeValue::Bool),
+    optional("autoIndex", AttributeValue::Bool),
+    optional("allowMultiOverlap", AttributeValue::Bool),
+    optional(
+        "datelineRule",
+        AttributeValue::OneOf(&["width180", "ccwRect", "none"]),
+    ),
+    optional(
+        "validationRule",
+        AttributeValue::OneOf(&["error", "none", "repairBuffer0", "repairConvexHull"]),
+    ),
+    optional("pointsOnly", AttributeValue::Bool),
+];
+
+const CURRENCY_ATTRIBUTES: [ClassAttribute; 7] = [
+    required("amountLongSuffix", AttributeValue::Text),
+    required("codeStrSuffix", AttributeValue::Text),
+    optional("defaultCurrency", AttributeValue::Currency),
+    optional("currencyConfig", AttributeValue::Text),
+    optional("providerClass", AttributeValue::Text),
+    optional("refreshInterval", AttributeValue::Integer(1, i64::MAX)),
+    optional("ratesFileLocation", AttributeValue::Text),
+];
+
+const ENUM_ATTRIBUTES: [ClassAttribute; 2] = [
+    required("enumsConfig", AttributeValue::Text),
+    required("enumName", AttributeValue::Text),
+];
+
+// Lucene's default limit of the number of dimensions
+const MAX_VECTOR_DIMENSION: i64 = 1024;
+
+const DENSE_VECTOR_ATTRIBUTES: [ClassAttribute; 6] = [
+    required(
+        "vectorDimension",
+        AttributeValue::Integer(1, MAX_VECTOR_DIMENSION),
+    ),
+    optional(
+        "similarityFunction",
+        AttributeValue::OneOf(&["euclidean", "dot_product", "cosine", "max_inner_product"]),
+    ),
+    optional("knnAlgorithm", AttributeValue::OneOf(&["hnsw"])),
+    optional("hnswMaxConnections", AttributeValue::Integer(1, 512)),
+    optional("hnswBeamWidth", AttributeValue::Integer(1, 3200)),
+    optional(
+        "vectorEncoding",
+        AttributeValue::OneOf(&["FLOAT32", "BYTE"]),
+    ),
+];
+
+const SCALAR_QUANTIZATION_ATTRIBUTES: [ClassAttribute; 4] = [
+    optional("bits", AttributeValue::OneOf(&["4", "7"])),
+    optional("confidenceInterval", AttributeValue::Decimal(0.9, 1.0)),
+    optional("dynamicConfidenceInterval", AttributeValue::Bool),
+    optional("compress", AttributeValue::Bool),
+];
+
+// the vector classes and the Solr version that introduced them
+const VECTOR_CLASSES: [(&str, (u32, u32)); 3] = [
+    ("DenseVectorField", (9, 0)),
+    ("ScalarQuantizedDenseVectorField", (9, 8)),
+    ("BinaryQuantizedDenseVectorField", (9, 9)),
+];
+
+// the provider CurrencyFieldType uses without a providerClass, which reads currencyConfig
+const FILE_EXCHANGE_RATE_PROVIDER: &str = "FileExchangeRateProvider";
+
+// the provider that downloads the rates from ratesFileLocation
+const OPEN_EXCHANGE_RATES_PROVIDER: &str = "OpenExchangeRatesOrgProvider";
+
+/// The attributes specific to a fieldType class, for the classes whose attributes are known.
+pub fn class_attributes(class_name: &str) -> Option<Vec<ClassAttribute>> {
+    let attributes = match class_name {
+        "LatLonPointSpatialField" => SPATIAL_ATTRIBUTES.to_vec(),
+        "SpatialRecursivePrefixTreeFieldType" | "RptWithGeometrySpatialField" => {
+            [&SPATIAL_ATTRIBUTES[..], &PREFIX_TREE_ATTRIBUTES[..]].concat()
+        }
+        "BBoxField" => [
+            &SPATIAL_ATTRIBUTES[..],
+            &[
+                required("numberType", AttributeValue::FieldType),
+                optional("booleanType", AttributeValue::FieldType),
+                optional("worldBounds", AttributeValue::Text),
+            ],
+        ]
+        .concat(),
+        "CurrencyFieldType" => CURRENCY_ATTRIBUTES.to_vec(),
+        "EnumFieldType" | "EnumField" => ENUM_ATTRIBUTES.to_vec(),
+        "DenseVectorField" | "BinaryQuantizedDenseVectorField" => DENSE_VECTOR_ATTRIBUTES.to_vec(),
+        "ScalarQuantizedDenseVectorField" => [
+            &DENSE_VECTOR_ATTRIBUTES[..],
+            &SCALAR_QUANTIZATION_ATTRIBUTES[..],
+        ]
+        .concat(),
+        "PointType" => vec![
+            optional("dimension", AttributeValue::Integer(1, i64::MAX)),
+            optional("subFieldSuffix", AttributeValue::Text),
+            optional("subFieldType", AttributeValue::FieldType),
+        ],
+        _ => return None,
+    };
+    Some(attributes)
+}
+
+fn check_attribute_value(schema: &Schema, value: AttributeValue, text: &str) -> Option<String> {
+    let valid = match value {
+        AttributeValue::Bool => text == "true" || text == "false",
+        AttributeValue::Integer(min, max) => text
+            .parse::<i64>()
+            .is_ok_and(|number| (min..=max).contains(&number)),
+        AttributeValue::Decimal(min, max) => text
+            .parse::<f64>()
+            .is_ok_and(|number| (min..=max).contains(&number)),
+        AttributeValue::OneOf(values) => values.contains(&text),
+        AttributeValue::FieldType => schema.field_type(text).is_some(),
+        AttributeValue::Currency => text.len() == 3 && text.chars().all(|c| c.is_ascii_uppercase()),
+        AttributeValue::Text => !text.is_empty(),
+    };
+    if valid {
+        return None;
+    }
+    Some(match value {
+        AttributeValue::Bool => "true or false".to_string(),
+        AttributeValue::Integer(min, i64::MAX) => format!("an integer of at least {}", min),
+        AttributeValue::Integer(min, max) => format!("an integer from {} to {}", min, max),
+        AttributeValue::Decimal(min, f64::MAX) => format!("a number of at least {}", min),
+        AttributeValue::Decimal(min, max) => format!("a number from {} to {}", min, max),
+        AttributeValue::OneOf(values) => format!("one of {}", values.join(", ")),
+        AttributeValue::FieldType => "the name of a fieldType".to_string(),
+        AttributeValue::Currency => "an ISO 4217 currency code such as USD".to_string(),
+        AttributeValue::Text => "a value".to_string(),
+    })
+}
+
+fn error(message: String) -> Finding {
+    Finding {
+        rule: "field-type-attribute",
+        severity: Severity::Error,
+        message,
+        fixes: Vec::new(),
+    }
+}
+
+/// Checks the class specific attributes of every fieldType against the catalog of its class,
+/// and the definitions and configset files they refer to.
+pub fn check_class_attributes(
+    schema: &Schema,
+    config: &Config,
+    resources: &Resources,
+) -> Vec<Finding> {
+    let mut findings = check_vector_fields(schema, config);
+    for field_type in &schema.field_types {
+        let Some(attributes) = class_attributes(field_type.class_name()) else {
+            continue;
+        };
+        for attr in &field_type.element.attributes {
+            let name = attr.name.local_name.as_str();
+            if name == "class"
+                || FIELD_TYPE_GENERAL_PROPERTIES.contains(&name)
+                || OPTIONAL_FIELD_PROPERTIES.contains(&name)
+            {
+                continue;
+            }
+            let Some(known) = attributes.iter().find(|known| known.name == name) else {
+                findings.push(error(format!(
+                    "The fieldType '{}' sets '{}', which a {} does not support.",
+                    field_type.name,
+                    name,
+                    field_type.class_name()
+                )));
+                continue;
+            };
+            if let Some(expected) = check_attribute_value(schema, known.value, &attr.value) {
+                findings.push(error(format!(
+                    "The fieldType '{}' sets {}=\"{}\", but it must be {}.",
+                    field_type.name, name, attr.value, expected
+                )));
+            }
+        }
+        for known in attributes.iter().filter(|known| known.required) {
+            if field_type.element.attribute(known.name).is_none() {
+                findings.push(error(format!(
+                    "The fieldType '{}' is a {} and needs '{}'.",
+                    field_type.name,
+                    field_type.class_name(),
+                    known.name
+                )));
+            }
+        }
+        findings.extend(check_companions(schema, field_type, resources));
+    }
+    findings
+}
+
+/// Checks that only the classes that analyze text have analyzers and text properties.
+pub fn check_analyzable_classes(schema: &Schema) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    for field_type in &schema.field_types {
+        let class_name = field_type.class_name();
+        // custom classes may analyze whatever they like
+        if !FIELD_TYPE_CLASSES.contains(&class_name)
+            && !DEPRECATED_FIELD_TYPES.contains(&class_name)
+        {
+            continue;
+        }
+        let analyzable = ANALYZABLE_CLASSES.contains(&class_name);
+        for analyzer in &field_type.analyzers {
+            let multiterm = analyzer.kind.as_deref() == Some("multiterm");
+            if !analyzable || (multiterm && !MULTITERM_CLASSES.contains(&class_name)) {
+                findings.push(Finding {
+                    rule: "analyzer-class",
+                    severity: Severity::Error,
+                    message: format!(
+                        "The fieldType '{}' has {} analyzer, but a {} doesn't analyze {}, so Solr rejects it.",
+                        field_type.name,
+                        analyzer.kind.as_deref().map_or("an".to_string(), |kind| format!("a {}", kind)),
+                        class_name,
+                        if multiterm && analyzable { "multiterm queries" } else { "its values" }
+                    ),
+                    fixes: Vec::new(),
+                });
+            }
+        }
+        if analyzable {
+            continue;
+        }
+        for property in TEXT_PROPERTIES {
+            if field_type.element.attribute(property).is_some() {
+                findings.push(Finding {
+                    rule: "text-property",
+                    severity: Severity::Warning,
+                    message: format!(
+                        "The fieldType '{}' sets '{}', which means nothing for a {} since it doesn't analyze text.",
+                        field_type.name, property, class_name
+                    ),
+                    fixes: vec![remove_attribute(&field_type.element, property)],
+                });
+            }
+        }
+    }
+    findings
+}
+
+fn check_vector_fields(schema: &Schema, config: &Config) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    for field_type in &schema.field_types {
+        let Some((class_name, since)) = VECTOR_CLASSES
+            .iter()
+            .find(|(class_name, _)| *class_name == field_type.class_name())
+        else {
+            continue;
+        };
+        if let Some(version) = config.solr_version().filter(|version| version < since) {
+            findings.push(error(format!(
+                "The fieldType '{}' is a {}, which Solr {}.{} does not have; it was added in Solr {}.{}.",
+                field_type.name, class_name, version.0, version.1, since.0, since.1
+            )));
+        }
+        // a document holds a single vector per field
+        for field in schema.fields.iter().chain(&schema.dynamic_fields) {
+            if field.type_name == field_type.name && schema.property(field, "multiValued") {
+                findings.push(error(format!(
+                    "The {} '{}' of the vector fieldType '{}' must not be multiValued.",
+                    if field.dynamic {
+                        "dynamicField"
+                    } else {
+                        "field"
+                    },
+                    field.name,
+                    field_type.name
+                )));
+            }
+        }
+    }
+    findings
+}
+
+// definitions the fieldType relies on besides its own attributes
+fn check_companions(
+    schema: &Schema,
+    field_type: &FieldType,
+    resources: &Resources,
+) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    let attribute = |name: &str| field_type.element.attribute(name);
+    match field_type.class_name() {
+        "BBoxField" => {
+            let number_type = field_type
+                .element
+                .attribute("numberType")
+                .and_then(|name| schema.field_type(name));
+            if let Some(number_type) = number_type {
+                // the bounding box is stored in four numeric fields that are searched with docValues
+                let is_double =
+                    ["DoublePointField", "TrieDoubleField"].contains(&number_type.class_name());
+                let doc_values = schema.type_property(number_type, "docValues");
+                if !is_double || !doc_values {
+                    findings.push(error(format!(
+                        "The numberType '{}' of the fieldType '{}' must be a DoublePointField or TrieDoubleField with docValues=\"true\".",
+                        number_type.name, field_type.name
+                    )));
+                }
+            }
+        }
+        "PointType" => {
+            let suffix = field_type.element.attribute("subFieldSuffix");
+            let sub_type = field_type.element.attribute("subFieldType");
+            match (suffix, sub_type) {
+                (Some(_), Some(_)) | (None, None) => findings.push(error(format!(
+                    "The fieldType '{}' needs exactly one of 'subFieldSuffix' and 'subFieldType'.",
+                    field_type.name
+                ))),
+                (Some(suffix), None) => {
+                    // the sub fields are named like `location_0_d`
+                    let sub_field = format!("x_0{}", suffix);
+                    let matched = schema
+                        .dynamic_fields
+                        .iter()
+                        .any(|field| matches_pattern(&field.name, &sub_field));
+                    if !matched {
+                        findings.push(error(format!(
+                            "The fieldType '{}' has subFieldSuffix=\"{}\", but no dynamicField '*{}' holds its sub fields.",
+                            field_type.name, suffix, suffix
+                        )));
+                    }
+                }
+                (None, Some(_)) => {}
+            }
+        }
+        "CurrencyFieldType" => {
+            // the amount and the currency code are indexed into dynamic fields
+            for (suffix, class_name) in [
+                (attribute("amountLongSuffix"), "LongPointField"),
+                (attribute("codeStrSuffix"), "StrField"),
+            ] {
+                let Some(suffix) = suffix else {
+                    continue;
+                };
+                let sub_field = schema.resolve_field(&format!("amount{}", suffix));
+                match sub_field.filter(|field| field.dynamic) {
+                    Some(sub_field) => {
+                        let sub_class = schema
+                            .type_of(sub_field)
+                            .map(FieldType::class_name)
+                            .unwrap_or_default();
+                        if sub_class != class_name {
+                            findings.push(error(format!(
+                                "The dynamicField '{}' for the fieldType '{}' must be a {}, but it is a {}.",
+                                sub_field.name, field_type.name, class_name, sub_class
+                            )));
+                        }
+                    }
+                    None => findings.push(error(format!(
+                        "The fieldType '{}' needs a dynamicField '*{}' of a {}.",
+                        field_type.name, suffix, class_name
+                    ))),
+                }
+            }
+            let provider = attribute("providerClass").unwrap_or(FILE_EXCHANGE_RATE_PROVIDER);
+            if provider.ends_with(FILE_EXCHANGE_RATE_PROVIDER) {
+                match attribute("currencyConfig") {
+                    Some(file) if resources.read(file).is_none() => findings.push(error(format!(
+                        "The currencyConfig '{}' of the fieldType '{}' is not in the configset.",
+                        file, field_type.name
+                    ))),
+                    Some(_) => {}
+                    None => findings.push(error(format!(
+                        "The fieldType '{}' needs a currencyConfig file or a providerClass.",
+                        field_type.name
+                    ))),
+                }
+            }
+            if provider.ends_with(OPEN_EXCHANGE_RATES_PROVIDER)
+                && attribute("ratesFileLocation").is_none()
+            {
+                findings.push(error(format!(
+                    "The fieldType '{}' uses the {} and needs a ratesFileLocation.",
+                    field_type.name, OPEN_EXCHANGE_RATES_PROVIDER
+                )));
+            }
+        }
+        "EnumFieldType" | "EnumField" => {
+            let (Some(file), Some(enum_name)) = (attribute("enumsConfig"), attribute("enumName"))
+            else {
+                return findings;
+            };
+            match resources.read(file) {
+                None => findings.push(error(format!(
+                    "The enumsConfig '{}' of the fieldType '{}' is not in the configset.",
+                    file, field_type.name
+                ))),
+                Some(content) => match enum_values(&content, enum_name) {
+                    None => findings.push(error(format!(
+                        "The enumsConfig '{}' does not define the enum '{}' of the fieldType '{}'.",
+                        file, enum_name, field_type.name
+                    ))),
+                    Some(values) if values.is_empty() => findings.push(error(format!(
+                        "The enum '{}' in '{}' has no values.",
+                        enum_name, file
+                    ))),
+                    Some(_) => {}
+                },
+            }
+        }
+        _ => {}
+    }
+    findings
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::classes::{check_analyzable_classes, check_class_attributes};
+    use crate::config::Config;
+    use crate::model::Schema;
+    use crate::resources::Resources;
+    use crate::tree::parse_document;
+    use std::io::Cursor;
+
+    fn check_with(example: &str, resources: &Resources) -> Vec<String> {
+        let schema = Schema::from_document(&parse_document(Cursor::new(example)).unwrap());
+        check_class_attributes(&schema, &Config::default(), resources)
+            .into_iter()
+            .map(|finding| finding.to_string())
+            .collect()
+    }
+
+    fn check(example: &str) -> Vec<String> {
+        check_with(example, &Resources::default())
+    }
+
+    #[test]
+    fn test_spatial_attributes() {
+        let example = r#"<schema version="1.6">
+  <fieldType name="location" class="solr.LatLonPointSpatialField" docValues="true" geo="yes" />
+  <fieldType name="location_rpt" class="solr.SpatialRecursivePrefixTreeFieldType" geo="true" distErrPct="0.9" maxDistErr="0.001" distanceUnits="feet" />
+  <fieldType name="shape" class="solr.RptWithGeometrySpatialField" prefixTree="s2" maxLevels="0" units="degrees" />
+</schema>"#;
+        assert_eq!(
+            check(example),
+            vec![
+                "error[field-type-attribute]: The fieldType 'location' sets geo=\"yes\", but it must be true or false.",
+                "error[field-type-attribute]: The fieldType 'location_rpt' sets distErrPct=\"0.9\", but it must be a number from 0 to 0.5.",
+                "error[field-type-attribute]: The fieldType 'location_rpt' sets distanceUnits=\"feet\", but it must be one of kilometers, miles, degrees.",
+                "error[field-type-attribute]: The fieldType 'shape' sets maxLevels=\"0\", but it must be an integer of at least 1.",
+                "error[field-type-attribute]: The fieldType 'shape' sets 'units', which a RptWithGeometrySpatialField does not support.",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_companion_definitions() {
+        let example = r#"<schema version="1.6">
+  <dynamicField name="*_d" type="pdouble" />
+  <fieldType name="bbox" class="solr.BBoxField" numberType="pfloat" />
+  <fieldType name="bbox_missing" class="solr.BBoxField" numberType="missing" />
+  <fieldType name="bbox_without" class="solr.BBoxField" />
+  <fieldType name="point" class="solr.PointType" dimension="2" subFieldSuffix="_d" />
+  <fieldType name="point_3d" class="solr.PointType" dimension="3" subFieldSuffix="_p" />
+  <fieldType name="pfloat" class="solr.FloatPointField" docValues="true" />
+  <fieldType name="pdouble" class="solr.DoublePointField" docValues="true" />
+</schema>"#;
+        assert_eq!(
+            check(example),
+            vec![
+                "error[field-type-attribute]: The numberType 'pfloat' of the fieldType 'bbox' must be a DoublePointField or TrieDoubleField with docValues=\"true\".",
+                "error[field-type-attribute]: The fieldType 'bbox_missing' sets numberType=\"missing\", but it must be the name of a fieldType.",
+                "error[field-type-attribute]: The fieldType 'bbox_without' is a BBoxField and needs 'numberType'.",
+                "error[field-type-attribute]: The fieldType 'point_3d' has subFieldSuffix=\"_p\", but no dynamicField '*_p' holds its sub fields.",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_bbox_field_type() {
+        let example = r#"<schema version="1.7">
+  <fieldType name="bbox" class="solr.BBoxField" numberType="pdouble" booleanType="bool" />
+  <fieldType name="bbox_missing" class="solr.BBoxField" numberType="pdouble" booleanType="missing" />
+  <fieldType name="bbox_trie" class="solr.BBoxField" numberType="tdouble" />
+  <fieldType name="pdouble" class="solr.DoublePointField" />
+  <fieldType name="tdouble" class="solr.TrieDoubleField" docValues="true" />
+  <fieldType name="bool" class="solr.BoolField" />
+</schema>"#;
+        assert_eq!(
+            check(example),
+            vec![
+                "error[field-type-attribute]: The fieldType 'bbox_missing' sets booleanType=\"missing\", but it must be the name of a fieldType.",
+            ]
+        );
+        // docValues are only enabled by default since schema version 1.7
+        assert_eq!(
+            check(&example.replace("version=\"1.7\"", "version=\"1.6\"")),
+            vec![
+                "error[field-type-attribute]: The numberType 'pdouble' of the fieldType 'bbox' must be a DoublePointField or TrieDoubleField with docValues=\"true\".",
+                "error[field-type-attribute]: The fieldType 'bbox_missing' sets booleanType=\"missing\", but it must be the name of a fieldType.",
+                "error[field-type-attribute]: The numberType 'pdouble' of the fieldType 'bbox_missing' must be a DoublePointField or TrieDoubleField with docValues=\"true\".",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_currency_field_type() {
+        let example = r#"<schema version="1.6">
+  <dynamicField name="*_l_ns" type="plong" indexed="true" stored="false" />
+  <dynamicField name="*_s_ns" type="pint" indexed="true" stored="false" />
+  <dynamicField name="*_s" type="string" />
+  <fieldType name="currency" class="solr.CurrencyFieldType" amountLongSuffix="_l_ns" codeStrSuffix="_s_ns" defaultCurrency="usd" currencyConfig="currency.xml" />
+  <fieldType name="currency_missing" class="solr.CurrencyFieldType" amountLongSuffix="_l" codeStrSuffix="_s_ns" currencyConfig="rates.xml" />
+  <fieldType name="currency_oer" class="solr.CurrencyFieldType" amountLongSuffix="_l_ns" codeStrSuffix="_s_ns" providerClass="solr.OpenExchangeRatesOrgProvider" />
+  <fieldType name="currency_rates" class="solr.CurrencyFieldType" amountLongSuffix="_l_ns" codeStrSuffix="_s" providerClass="solr.OpenExchangeRatesOrgProvider" ratesFileLocation="https://openexchangerates.org/api/latest.json?app_id=APP_ID" refreshInterval="60" />
+  <fieldType name="plong" class="solr.LongPointField" />
+  <fieldType name="pint" class="solr.IntPointField" />
+  <fieldType name="string" class="solr.StrField" />
+</schema>"#;
+        let resources = Resources::default().with_file("currency.xml", "<currencyConfig />");
+        assert_eq!(
+            check_with(example, &resources),
+            vec![
+                "error[field-type-attribute]: The fieldType 'currency' sets defaultCurrency=\"usd\", but it must be an ISO 4217 currency code such as USD.",
+                "error[field-type-attribute]: The dynamicField '*_s_ns' for the fieldType 'currency' must be a StrField, but it is a IntPointField.",
+                "error[field-type-attribute]: The fieldType 'currency_missing' needs a dynamicField '*_l' of a LongPointField.",
+                "error[field-type-attribute]: The dynamicField '*_s_ns' for the fieldType 'currency_missing' must be a StrField, but it is a IntPointField.",
+                "error[field-type-attribute]: The currencyConfig 'rates.xml' of the fieldType 'currency_missing' is not in the configset.",
+                "error[field-type-attribute]: The dynamicField '*_s_ns' for the fieldType 'currency_oer' must be a StrField, but it is a IntPointField.",
+                "error[field-type-attribute]: The fieldType 'currency_oer' uses the OpenExchangeRatesOrgProvider and needs a ratesFileLocation.",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_enum_field_type() {
+        let example = r#"<schema version="1.6">
+  <fieldType name="severity" class="solr.EnumFieldType" enumsConfig="enumsConfig.xml" enumName="severity" />
+  <fieldType name="priority" class="solr.EnumFieldType" enumsConfig="enumsConfig.xml" enumName="priority" />
+  <fieldType name="status" class="solr.EnumFieldType" enumsConfig="status.xml" enumName="status" />
+  <fieldType name="level" class="solr.EnumFieldType" enumName="level" />
+</schema>"#;
+        let resources = Resources::default().with_file(
+            "enumsConfig.xml",
+            r#"<enumsConfig><enum name="severity"><value>Low</value></enum></enumsConfig>"#,
+        );
+        assert_eq!(
+            check_with(example, &resources),
+            vec![
+                "error[field-type-attribute]: The enumsConfig 'enumsConfig.xml' does not define the enum 'priority' of the fieldType 'priority'.",
+                "error[field-type-attribute]: The enumsConfig 'status.xml' of the fieldType 'status' is not in the configset.",
+                "error[field-type-attribute]: The fieldType 'level' is a EnumFieldType and needs 'enumsConfig'.",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_dense_vector_field_type() {
+        let example = r#"<schema version="1.6">
+  <field name="vector" type="knn_vector" />
+  <field name="vectors" type="knn_vector" multiValued="true" />
+  <fieldType name="knn_vector" class="solr.DenseVectorField" vectorDimension="4" similarityFunction="cosine" knnAlgorithm="hnsw" hnswMaxConnections="10" hnswBeamWidth="40" />
+  <fieldType name="knn_large" class="solr.DenseVectorField" vectorDimension="4096" similarityFunction="manhattan" vectorEncoding="INT8" />
+  <fieldType name="knn_missing" class="solr.DenseVectorField" hnswMaxConnections="0" />
+  <fieldType name="knn_quantized" class="solr.ScalarQuantizedDenseVectorField" vectorDimension="4" bits="8" />
+</schema>"#;
+        assert_eq!(
+            check(example),
+            vec![
+                "error[field-type-attribute]: The field 'vectors' of the vector fieldType 'knn_vector' must not be multiValued.",
+                "error[field-type-attribute]: The fieldType 'knn_large' sets vectorDimension=\"4096\", but it must be an integer from 1 to 1024.",
+                "error[field-type-attribute]: The fieldType 'knn_large' sets similarityFunction=\"manhattan\", but it must be one of euclidean, dot_product, cosine, max_inner_product.",
+                "error[field-type-attribute]: The fieldType 'knn_large' sets vectorEncoding=\"INT8\", but it must be one of FLOAT32, BYTE.",
+                "error[field-type-attribute]: The fieldType 'knn_missing' sets hnswMaxConnections=\"0\", but it must be an integer from 1 to 512.",
+                "error[field-type-attribute]: The fieldType 'knn_missing' is a DenseVectorField and needs 'vectorDimension'.",
+                "error[field-type-attribute]: The fieldType 'knn_quantized' sets bits=\"8\", but it must be one of 4, 7.",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_quantized_vector_field_needs_solr_version() {
+        let example = r#"<schema version="1.6">
+  <fieldType name="knn_quantized" class="solr.ScalarQuantizedDenseVectorField" vectorDimension="4" />
+</schema>"#;
+        let schema = Schema::from_document(&parse_document(Cursor::new(example)).unwrap());
+        let config: Config = toml::from_str("solr-version = \"9.6\"\n").unwrap();
+        let findings = check_class_attributes(&schema, &config, &Resources::default());
+        assert_eq!(
+            findings[0].to_string(),
+            "error[field-type-attribute]: The fieldType 'knn_quantized' is a ScalarQuantizedDenseVectorField, which Solr 9.6 does not have; it was added in Solr 9.8."
+        );
+    }
+
+    #[test]
+    fn test_analyzable_classes() {
+        let example = r#"<schema version="1.6">
+  <fieldType name="string" class="solr.StrField" positionIncrementGap="0">
+    <analyzer>
+      <tokenizer class="solr.KeywordTokenizerFactory" />
+    </analyzer>
+  </fieldType>
+  <fieldType name="pint" class="solr.IntPointField" autoGeneratePhraseQueries="true" />
+  <fieldType name="preanalyzed" class="solr.PreAnalyzedField" positionIncrementGap="100">
+    <analyzer type="multiterm">
+      <tokenizer class="solr.KeywordTokenizerFactory" />
+    </analyzer>
+  </fieldType>
+  <fieldType name="text" class="solr.TextField" positionIncrementGap="100" enableGraphQueries="true">
+    <analyzer type="multiterm">
+      <tokenizer class="solr.KeywordTokenizerFactory" />
+    </analyzer>
+  </fieldType>
+  <fieldType name="custom" class="com.example.CustomField">
+    <analyzer>
+      <tokenizer class="solr.KeywordTokenizerFactory" />
+    </analyzer>
+  </fieldType>
+</schema>"#;
+        let schema = Schema::from_document(&parse_document(Cursor::new(example)).unwrap());
+        let findings: Vec<String> = check_analyzable_classes(&schema)
+            .into_iter()
+            .map(|finding| finding.to_string())
+            .collect();
+        assert_eq!(
+            findings,
+            vec![
+                "error[analyzer-class]: The fieldType 'string' has an analyzer, but a StrField doesn't analyze its values, so Solr rejects it.",
+                "warning[text-property]: The fieldType 'pint' sets 'autoGeneratePhraseQueries', which means nothing for a IntPointField since it doesn't analyze text.",
+            ]
+        );
+    }
+}
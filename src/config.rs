@@ -0,0 +1,101 @@
+use std::fs;
+use std::io;
+
+use regex::Regex;
+use serde::Deserialize;
+
+/// Settings read from a TOML file given with `--config`; every setting has a default.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, rename_all = "kebab-case", deny_unknown_fields)]
+pub struct Config {
+    /// The Solr version the schema is deployed to, e.g. `9.8`; checks of version specific
+    /// features are skipped without it.
+    pub solr_version: Option<String>,
+    pub profile: Profile,
+    pub naming: NamingConfig,
+}
+
+/// How much of Solr's leniency is accepted; `strict` also reports what only works by accident,
+/// such as fields that are created while indexing.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Profile {
+    #[default]
+    Default,
+    Strict,
+}
+
+/// The patterns names must match, by the kind of definition.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, rename_all = "kebab-case", deny_unknown_fields)]
+pub struct NamingConfig {
+    pub field: String,
+    /// Applies to a dynamicField pattern without its `*`, e.g. `_txt_en` for `*_txt_en`.
+    pub dynamic_field: String,
+    pub field_type: String,
+}
+
+impl Default for NamingConfig {
+    // Solr recommends alphanumeric and underscore characters that don't start with a digit
+    fn default() -> Self {
+        NamingConfig {
+            field: "^[A-Za-z_][A-Za-z0-9_]*$".to_string(),
+            dynamic_field: "^[A-Za-z0-9_]+$".to_string(),
+            field_type: "^[A-Za-z_][A-Za-z0-9_]*$".to_string(),
+        }
+    }
+}
+
+impl Config {
+    pub fn load(path: Option<&str>) -> io::Result<Config> {
+        let Some(path) = path else {
+            return Ok(Config::default());
+        };
+        let invalid =
+            |e: String| io::Error::new(io::ErrorKind::InvalidData, format!("{}: {}", path, e));
+        let config: Config =
+            toml::from_str(&fs::read_to_string(path)?).map_err(|e| invalid(e.to_string()))?;
+        for pattern in [
+            &config.naming.field,
+            &config.naming.dynamic_field,
+            &config.naming.field_type,
+        ] {
+            Regex::new(pattern).map_err(|e| invalid(e.to_string()))?;
+        }
+        if let Some(version) = &config.solr_version {
+            parse_version(version)
+                .ok_or_else(|| invalid(format!("invalid solr-version '{}'", version)))?;
+        }
+        Ok(config)
+    }
+
+    /// The major and minor part of `solr-version`.
+    pub fn solr_version(&self) -> Option<(u32, u32)> {
+        self.solr_version.as_deref().and_then(parse_version)
+    }
+}
+
+fn parse_version(version: &str) -> Option<(u32, u32)> {
+    let mut parts = version.split('.');
+    let major = parts.next()?.parse().ok()?;
+    let minor = parts.next().map_or(Some(0), |minor| minor.parse().ok())?;
+    Some((major, minor))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::config::{Config, Profile};
+
+    #[test]
+    fn test_partial_config() {
+        let config: Config = toml::from_str("[naming]\nfield = \"^[a-z_]+$\"\n").unwrap();
+        assert_eq!(config.naming.field, "^[a-z_]+$");
+        assert_eq!(config.naming.field_type, "^[A-Za-z_][A-Za-z0-9_]*$");
+        assert!(toml::from_str::<Config>("[naming]\nfields = \"x\"\n").is_err());
+        let config: Config = toml::from_str("solr-version = \"9.8.1\"\n").unwrap();
+        assert_eq!(config.solr_version(), Some((9, 8)));
+        assert_eq!(config.profile, Profile::Default);
+        let config: Config = toml::from_str("profile = \"strict\"\n").unwrap();
+        assert_eq!(config.profile, Profile::Strict);
+    }
+}
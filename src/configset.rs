@@ -0,0 +1,171 @@
+use serde_json::Value;
+
+use crate::resources::Resources;
+use crate::rules::{Finding, Severity};
+use crate::tree::{parse_document, Element};
+
+/// The schema file names in the order the managed schema factory looks for them.
+pub(crate) const MANAGED_SCHEMA_FILES: [&str; 3] =
+    ["managed-schema.xml", "managed-schema", "schema.xml"];
+
+const CLASSIC_SCHEMA_FILE: &str = "schema.xml";
+
+// the schemaFactory that only reads schema.xml and never writes a managed schema
+const CLASSIC_SCHEMA_FACTORY: &str = "ClassicIndexSchemaFactory";
+
+fn finding(severity: Severity, message: String) -> Finding {
+    Finding {
+        rule: "configset",
+        severity,
+        message,
+        fixes: Vec::new(),
+    }
+}
+
+fn schema_factory(resources: &Resources) -> Option<Element> {
+    let solrconfig = parse_document(resources.read("solrconfig.xml")?.as_bytes()).ok()?;
+    let factory = solrconfig
+        .root
+        .elements()
+        .find(|element| element.local_name() == "schemaFactory")
+        .cloned();
+    factory
+}
+
+/// Finds the schema file Solr loads from the configset, with a warning if the configset has
+/// several of them; `None` if it has none that Solr would load. The managed schema factory
+/// reads the `managedSchemaResourceName` of its configuration before falling back to
+/// schema.xml.
+pub fn find_schema_file(resources: &Resources) -> (Option<String>, Vec<Finding>) {
+    let factory = schema_factory(resources);
+    let classic = factory
+        .as_ref()
+        .and_then(|factory| factory.attribute("class"))
+        .is_some_and(|class| class.ends_with(CLASSIC_SCHEMA_FACTORY));
+    let resource_name = factory.as_ref().and_then(|factory| {
+        factory
+            .elements()
+            .find(|element| element.attribute("name") == Some("managedSchemaResourceName"))
+            .map(Element::text)
+    });
+    let candidates: Vec<String> = match resource_name {
+        Some(name) if !classic => vec![name, CLASSIC_SCHEMA_FILE.to_string()],
+        _ => MANAGED_SCHEMA_FILES.map(str::to_string).to_vec(),
+    };
+    let present: Vec<String> = candidates
+        .into_iter()
+        .filter(|file| resources.read_bytes(file).is_some())
+        .collect();
+    let used = match classic {
+        true => present.iter().find(|file| *file == CLASSIC_SCHEMA_FILE),
+        false => present.first(),
+    };
+    let mut findings = Vec::new();
+    if let (Some(used), true) = (used, present.len() > 1) {
+        findings.push(finding(
+            Severity::Warning,
+            format!(
+                "The configset has several schema files ({}); Solr loads '{}' and ignores the others.",
+                present.join(", "),
+                used
+            ),
+        ));
+    }
+    (used.cloned(), findings)
+}
+
+/// Checks the configset files the schema doesn't refer to itself.
+pub fn check_configset(resources: &Resources) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    match resources.read("solrconfig.xml") {
+        None => findings.push(finding(
+            Severity::Error,
+            "The configset has no solrconfig.xml, which every core needs.".to_string(),
+        )),
+        Some(content) => {
+            if let Err(e) = parse_document(content.as_bytes()) {
+                findings.push(finding(
+                    Severity::Error,
+                    format!("The solrconfig.xml is not well-formed: {}.", e),
+                ));
+            }
+        }
+    }
+    // request parameter sets that handlers refer to with useParams
+    if let Some(content) = resources.read("params.json") {
+        match serde_json::from_str::<Value>(&content) {
+            Ok(params) if params["params"].is_object() => {}
+            Ok(_) => findings.push(finding(
+                Severity::Error,
+                "The params.json has no 'params' object.".to_string(),
+            )),
+            Err(e) => findings.push(finding(
+                Severity::Error,
+                format!("The params.json is not valid JSON: {}.", e),
+            )),
+        }
+    }
+    findings
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::configset::{check_configset, find_schema_file};
+    use crate::resources::Resources;
+
+    const SOLRCONFIG: &str = r#"<config><luceneMatchVersion>9.8</luceneMatchVersion></config>"#;
+
+    #[test]
+    fn test_find_schema_file() {
+        let resources = Resources::default()
+            .with_file("solrconfig.xml", SOLRCONFIG)
+            .with_file("managed-schema", "<schema />")
+            .with_file("schema.xml", "<schema />");
+        let (file, findings) = find_schema_file(&resources);
+        assert_eq!(file.as_deref(), Some("managed-schema"));
+        assert_eq!(
+            findings[0].to_string(),
+            "warning[configset]: The configset has several schema files (managed-schema, schema.xml); Solr loads 'managed-schema' and ignores the others."
+        );
+
+        let classic = resources.with_file(
+            "solrconfig.xml",
+            r#"<config><schemaFactory class="ClassicIndexSchemaFactory" /></config>"#,
+        );
+        assert_eq!(find_schema_file(&classic).0.as_deref(), Some("schema.xml"));
+        assert_eq!(find_schema_file(&Resources::default()).0, None);
+
+        let renamed = classic
+            .with_file(
+                "solrconfig.xml",
+                r#"<config><schemaFactory class="ManagedIndexSchemaFactory"><str name="managedSchemaResourceName">books-schema.xml</str></schemaFactory></config>"#,
+            )
+            .with_file("books-schema.xml", "<schema />");
+        let (file, findings) = find_schema_file(&renamed);
+        assert_eq!(file.as_deref(), Some("books-schema.xml"));
+        assert_eq!(
+            findings[0].to_string(),
+            "warning[configset]: The configset has several schema files (books-schema.xml, schema.xml); Solr loads 'books-schema.xml' and ignores the others."
+        );
+    }
+
+    #[test]
+    fn test_check_configset() {
+        let resources = Resources::default().with_file("params.json", r#"{"params": []}"#);
+        let findings: Vec<String> = check_configset(&resources)
+            .into_iter()
+            .map(|finding| finding.to_string())
+            .collect();
+        assert_eq!(
+            findings,
+            vec![
+                "error[configset]: The configset has no solrconfig.xml, which every core needs.",
+                "error[configset]: The params.json has no 'params' object.",
+            ]
+        );
+        let resources = Resources::default()
+            .with_file("solrconfig.xml", SOLRCONFIG)
+            .with_file("params.json", r#"{"params": {"query": {"rows": 10}}}"#);
+        assert!(check_configset(&resources).is_empty());
+    }
+}
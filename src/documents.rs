@@ -0,0 +1,405 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use serde_json::Value;
+
+use crate::model::Schema;
+use crate::rules::{Finding, Severity};
+use crate::tree::{parse_document, Element};
+use crate::values::check_value;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum DocumentValue {
+    Single(String),
+    Multiple(Vec<String>),
+    Children(Vec<SampleDocument>),
+}
+
+/// A document as it would be sent to Solr, with the values of each field in their textual form.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct SampleDocument {
+    pub fields: Vec<(String, DocumentValue)>,
+}
+
+impl SampleDocument {
+    fn push(&mut self, name: &str, value: String) {
+        match self.fields.iter_mut().find(|(field, _)| field == name) {
+            Some((_, DocumentValue::Single(first))) => {
+                let first = std::mem::take(first);
+                self.set(name, DocumentValue::Multiple(vec![first, value]));
+            }
+            Some((_, DocumentValue::Multiple(values))) => values.push(value),
+            _ => self
+                .fields
+                .push((name.to_string(), DocumentValue::Single(value))),
+        }
+    }
+
+    fn set(&mut self, name: &str, value: DocumentValue) {
+        match self.fields.iter_mut().find(|(field, _)| field == name) {
+            Some((_, existing)) => *existing = value,
+            None => self.fields.push((name.to_string(), value)),
+        }
+    }
+
+    fn contains(&self, name: &str) -> bool {
+        self.fields.iter().any(|(field, _)| field == name)
+    }
+}
+
+/// Reads JSON, JSONL, CSV or Solr XML `<add><doc>` files, chosen by the file extension.
+pub fn read_documents(path: &str) -> io::Result<Vec<SampleDocument>> {
+    let content = fs::read_to_string(path)?;
+    let invalid =
+        |e: String| io::Error::new(io::ErrorKind::InvalidData, format!("{}: {}", path, e));
+    let extension = Path::new(path)
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .unwrap_or_default();
+    match extension {
+        "json" => {
+            let value: Value =
+                serde_json::from_str(&content).map_err(|e| invalid(e.to_string()))?;
+            Ok(match value {
+                Value::Array(values) => values.iter().filter_map(json_document).collect(),
+                value => json_document(&value).into_iter().collect(),
+            })
+        }
+        "jsonl" | "ndjson" => content
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| {
+                let value: Value =
+                    serde_json::from_str(line).map_err(|e| invalid(e.to_string()))?;
+                json_document(&value).ok_or_else(|| invalid(format!("not a JSON object: {}", line)))
+            })
+            .collect(),
+        "csv" => {
+            let mut reader = csv::Reader::from_reader(content.as_bytes());
+            let headers = reader
+                .headers()
+                .map_err(|e| invalid(e.to_string()))?
+                .clone();
+            let mut documents = Vec::new();
+            for record in reader.records() {
+                let record = record.map_err(|e| invalid(e.to_string()))?;
+                let mut document = SampleDocument::default();
+                for (name, value) in headers.iter().zip(record.iter()) {
+                    if !value.is_empty() {
+                        document.push(name, value.to_string());
+                    }
+                }
+                documents.push(document);
+            }
+            Ok(documents)
+        }
+        "xml" => {
+            let document =
+                parse_document(content.as_bytes()).map_err(|e| invalid(e.to_string()))?;
+            Ok(document
+                .root
+                .elements()
+                .filter(|element| element.local_name() == "doc")
+                .map(xml_document)
+                .collect())
+        }
+        _ => Err(invalid(format!(
+            "unsupported document format '{}', expected json, jsonl, csv or xml",
+            extension
+        ))),
+    }
+}
+
+fn json_scalar(value: &Value) -> Option<String> {
+    match value {
+        Value::String(value) => Some(value.clone()),
+        Value::Number(value) => Some(value.to_string()),
+        Value::Bool(value) => Some(value.to_string()),
+        _ => None,
+    }
+}
+
+pub(crate) fn json_document(value: &Value) -> Option<SampleDocument> {
+    let object = value.as_object()?;
+    let mut document = SampleDocument::default();
+    for (name, value) in object {
+        let value = match value {
+            Value::Null => continue,
+            Value::Object(_) => DocumentValue::Children(json_document(value).into_iter().collect()),
+            Value::Array(values) if !values.is_empty() && values.iter().all(Value::is_object) => {
+                DocumentValue::Children(values.iter().filter_map(json_document).collect())
+            }
+            Value::Array(values) => {
+                DocumentValue::Multiple(values.iter().filter_map(json_scalar).collect())
+            }
+            value => DocumentValue::Single(json_scalar(value).unwrap_or_default()),
+        };
+        document.set(name, value);
+    }
+    Some(document)
+}
+
+fn xml_document(element: &Element) -> SampleDocument {
+    let mut document = SampleDocument::default();
+    let mut children = Vec::new();
+    for child in element.elements() {
+        match child.local_name() {
+            "field" => document.push(child.attribute("name").unwrap_or_default(), child.text()),
+            "doc" => children.push(xml_document(child)),
+            _ => {}
+        }
+    }
+    if !children.is_empty() {
+        document.set("_childDocuments_", DocumentValue::Children(children));
+    }
+    document
+}
+
+pub fn check_documents(schema: &Schema, documents: &[SampleDocument]) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    for (i, document) in documents.iter().enumerate() {
+        check_document(schema, document, &format!("{}", i + 1), &mut findings);
+    }
+    findings
+}
+
+/// Reports the dynamicFields that neither a copyField nor any of the documents use.
+pub fn check_unused_dynamic_fields(schema: &Schema, documents: &[SampleDocument]) -> Vec<Finding> {
+    let mut names: Vec<&str> = Vec::new();
+    collect_names(documents, &mut names);
+    for copy_field in &schema.copy_fields {
+        names.push(&copy_field.source);
+        names.push(&copy_field.dest);
+    }
+    schema
+        .dynamic_fields
+        .iter()
+        .filter(|dynamic_field| {
+            !names.iter().any(|name| {
+                *name == dynamic_field.name
+                    || schema
+                        .resolve_field(name)
+                        .is_some_and(|field| field == *dynamic_field)
+            })
+        })
+        .map(|dynamic_field| Finding {
+            rule: "unused-dynamic-field",
+            severity: Severity::Warning,
+            message: format!(
+                "The dynamicField '{}' is not used by any copyField or document.",
+                dynamic_field.name
+            ),
+            fixes: Vec::new(),
+        })
+        .collect()
+}
+
+fn collect_names<'a>(documents: &'a [SampleDocument], names: &mut Vec<&'a str>) {
+    for document in documents {
+        for (name, value) in &document.fields {
+            match value {
+                DocumentValue::Children(children) => collect_names(children, names),
+                _ => names.push(name),
+            }
+        }
+    }
+}
+
+fn error(rule: &'static str, message: String) -> Finding {
+    Finding {
+        rule,
+        severity: Severity::Error,
+        message,
+        fixes: Vec::new(),
+    }
+}
+
+fn check_document(
+    schema: &Schema,
+    document: &SampleDocument,
+    label: &str,
+    findings: &mut Vec<Finding>,
+) {
+    for (name, value) in &document.fields {
+        if let DocumentValue::Children(children) = value {
+            for (i, child) in children.iter().enumerate() {
+                check_document(schema, child, &format!("{}.{}", label, i + 1), findings);
+            }
+            continue;
+        }
+        let Some(field) = schema.resolve_field(name) else {
+            findings.push(error(
+                "document-unknown-field",
+                format!(
+                    "document {}: the field '{}' does not match any field or dynamicField.",
+                    label, name
+                ),
+            ));
+            continue;
+        };
+        let values = match value {
+            DocumentValue::Single(value) => vec![value.clone()],
+            DocumentValue::Multiple(values) => values.clone(),
+            DocumentValue::Children(_) => Vec::new(),
+        };
+        // arrays are rejected for single valued fields even when they hold one value
+        let array = matches!(value, DocumentValue::Multiple(_));
+        if array && !schema.property(field, "multiValued") {
+            findings.push(error(
+                "document-multiple-values",
+                format!(
+                    "document {}: the field '{}' has {} values but is not multiValued.",
+                    label,
+                    name,
+                    values.len()
+                ),
+            ));
+        }
+        let Some(field_type) = schema.type_of(field) else {
+            continue;
+        };
+        for value in &values {
+            if let Err(e) = check_value(field_type.class_name(), value) {
+                findings.push(error(
+                    "document-invalid-value",
+                    format!("document {}: {} in the field '{}'.", label, e, name),
+                ));
+            }
+        }
+    }
+
+    if let Some(unique_key) = &schema.unique_key {
+        if !document.contains(unique_key) {
+            findings.push(error(
+                "document-missing-unique-key",
+                format!(
+                    "document {}: the uniqueKey field '{}' is not set.",
+                    label, unique_key
+                ),
+            ));
+        }
+    }
+    for field in &schema.fields {
+        let unique_key = schema.unique_key.as_deref() == Some(field.name.as_str());
+        // Solr fills in defaults and copyField destinations before it checks the required fields
+        let filled = field.element.attribute("default").is_some()
+            || !schema.copy_fields_into(&field.name).is_empty();
+        if !unique_key
+            && !filled
+            && schema.property(field, "required")
+            && !document.contains(&field.name)
+        {
+            findings.push(error(
+                "document-missing-required-field",
+                format!(
+                    "document {}: the required field '{}' is not set.",
+                    label, field.name
+                ),
+            ));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::documents::{
+        check_documents, check_unused_dynamic_fields, json_document, xml_document, SampleDocument,
+    };
+    use crate::model::Schema;
+    use crate::tree::parse_document;
+    use std::io::Cursor;
+
+    const SCHEMA: &str = r#"<schema version="1.6">
+        <uniqueKey>id</uniqueKey>
+        <field name="id" type="uuid" required="true" />
+        <field name="title" type="string" required="true" />
+        <field name="count" type="pint" />
+        <field name="published" type="pdate" />
+        <field name="location" type="location" />
+        <field name="language" type="string" required="true" default="en" />
+        <field name="title_sort" type="string" required="true" />
+        <dynamicField name="*_b" type="boolean" />
+        <dynamicField name="*_ss" type="string" multiValued="true" />
+        <fieldType name="uuid" class="solr.UUIDField" />
+        <fieldType name="string" class="solr.StrField" />
+        <fieldType name="pint" class="solr.IntPointField" />
+        <fieldType name="pdate" class="solr.DatePointField" />
+        <fieldType name="boolean" class="solr.BoolField" />
+        <fieldType name="location" class="solr.LatLonPointSpatialField" />
+        <copyField source="title" dest="title_sort" />
+    </schema>"#;
+
+    fn check(documents: &[SampleDocument]) -> Vec<String> {
+        let schema = Schema::from_document(&parse_document(Cursor::new(SCHEMA)).unwrap());
+        check_documents(&schema, documents)
+            .into_iter()
+            .map(|finding| finding.to_string())
+            .collect()
+    }
+
+    fn json(value: &str) -> SampleDocument {
+        json_document(&serde_json::from_str(value).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_valid_json_document() {
+        let document = json(
+            r#"{"id": "f81d4fae-7dec-11d0-a765-00a0c91e6bf6", "title": "Solr", "count": 3,
+                "published": "2024-05-01T00:00:00Z", "location": "45.17,-93.87",
+                "open_b": true, "_b": false, "tags_ss": ["a", "b"]}"#,
+        );
+        assert!(check(&[document]).is_empty());
+    }
+
+    #[test]
+    fn test_invalid_json_document() {
+        let document = json(
+            r#"{"title": ["a", "b"], "count": "three", "published": "2024-05-01",
+                "open_b": "yes", "author": "x", "children": [{"id": "NEW"}]}"#,
+        );
+        assert_eq!(
+            check(&[document]),
+            vec![
+                "error[document-unknown-field]: document 1: the field 'author' does not match any field or dynamicField.",
+                "error[document-missing-required-field]: document 1.1: the required field 'title' is not set.",
+                "error[document-invalid-value]: document 1: 'three' is not a valid value for IntPointField in the field 'count'.",
+                "error[document-invalid-value]: document 1: 'yes' is not a valid value for BoolField in the field 'open_b'.",
+                "error[document-invalid-value]: document 1: '2024-05-01' is not a valid value for DatePointField in the field 'published'.",
+                "error[document-multiple-values]: document 1: the field 'title' has 2 values but is not multiValued.",
+                "error[document-missing-unique-key]: document 1: the uniqueKey field 'id' is not set.",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_xml_documents() {
+        let example = r#"<add>
+            <doc>
+                <field name="id">f81d4fae-7dec-11d0-a765-00a0c91e6bf6</field>
+                <field name="title">a</field>
+                <field name="title">b</field>
+            </doc>
+        </add>"#;
+        let add = parse_document(Cursor::new(example)).unwrap();
+        let documents: Vec<SampleDocument> = add.root.elements().map(xml_document).collect();
+        assert_eq!(
+            check(&documents),
+            vec!["error[document-multiple-values]: document 1: the field 'title' has 2 values but is not multiValued."]
+        );
+    }
+
+    #[test]
+    fn test_unused_dynamic_fields() {
+        let schema = Schema::from_document(&parse_document(Cursor::new(SCHEMA)).unwrap());
+        let findings: Vec<String> =
+            check_unused_dynamic_fields(&schema, &[json(r#"{"tags_ss": ["a"]}"#)])
+                .into_iter()
+                .map(|finding| finding.to_string())
+                .collect();
+        assert_eq!(
+            findings,
+            vec!["warning[unused-dynamic-field]: The dynamicField '*_b' is not used by any copyField or document."]
+        );
+    }
+}
@@ -0,0 +1,220 @@
+use std::collections::BTreeMap;
+
+use crate::model::{Analyzer, Schema};
+use crate::tree::Element;
+
+fn attributes(element: &Element) -> BTreeMap<&str, &str> {
+    element
+        .attributes
+        .iter()
+        .map(|attribute| (attribute.name.local_name.as_str(), attribute.value.as_str()))
+        .collect()
+}
+
+fn describe(value: Option<&&str>) -> String {
+    match value {
+        Some(value) => format!("\"{}\"", value),
+        None => "no value".to_string(),
+    }
+}
+
+// the attribute differences of two definitions of the same name
+fn compare_elements(kind: &str, name: &str, local: &Element, live: &Element) -> Vec<String> {
+    let (local, live) = (attributes(local), attributes(live));
+    let mut names: Vec<&str> = local.keys().chain(live.keys()).copied().collect();
+    names.sort();
+    names.dedup();
+    names
+        .into_iter()
+        .filter(|attribute| local.get(attribute) != live.get(attribute))
+        .map(|attribute| {
+            format!(
+                "The {} '{}' has {} {} locally and {} in the live schema.",
+                kind,
+                name,
+                attribute,
+                describe(local.get(attribute)),
+                describe(live.get(attribute))
+            )
+        })
+        .collect()
+}
+
+// an analyzer as its type and its components with their factories and attributes, so that
+// class names and SPI names compare equal
+fn analyzer_chain(analyzer: &Analyzer) -> String {
+    let components: Vec<String> = analyzer
+        .components
+        .iter()
+        .map(|component| {
+            let mut properties = attributes(&component.element);
+            properties.retain(|name, _| *name != "class" && *name != "name");
+            format!("{}{:?}", component.factory().to_lowercase(), properties)
+        })
+        .collect();
+    format!(
+        "{:?} {:?} {}",
+        analyzer.kind,
+        analyzer.class,
+        components.join(" ")
+    )
+}
+
+fn compare_definitions<'a>(
+    kind: &str,
+    local: impl Iterator<Item = (&'a str, &'a Element)>,
+    live: impl Iterator<Item = (&'a str, &'a Element)>,
+) -> Vec<String> {
+    let local: BTreeMap<&str, &Element> = local.collect();
+    let live: BTreeMap<&str, &Element> = live.collect();
+    let mut drift = Vec::new();
+    for (name, element) in &local {
+        match live.get(name) {
+            Some(live_element) => drift.extend(compare_elements(kind, name, element, live_element)),
+            None => drift.push(format!(
+                "The {} '{}' is only in the local schema.",
+                kind, name
+            )),
+        }
+    }
+    for name in live.keys().filter(|name| !local.contains_key(*name)) {
+        drift.push(format!(
+            "The {} '{}' is only in the live schema.",
+            kind, name
+        ));
+    }
+    drift
+}
+
+/// The differences between a local schema and the live schema of a running Solr: the uniqueKey,
+/// the fields, dynamicFields and fieldTypes by name with their attributes and analyzers, and the
+/// copyFields.
+pub fn compare_schemas(local: &Schema, live: &Schema) -> Vec<String> {
+    let mut drift = Vec::new();
+    if local.unique_key != live.unique_key {
+        drift.push(format!(
+            "The uniqueKey is {} locally and {} in the live schema.",
+            describe(local.unique_key.as_deref().as_ref()),
+            describe(live.unique_key.as_deref().as_ref())
+        ));
+    }
+    for (kind, local_fields, live_fields) in [
+        ("field", &local.fields, &live.fields),
+        ("dynamicField", &local.dynamic_fields, &live.dynamic_fields),
+    ] {
+        drift.extend(compare_definitions(
+            kind,
+            local_fields
+                .iter()
+                .map(|field| (field.name.as_str(), &field.element)),
+            live_fields
+                .iter()
+                .map(|field| (field.name.as_str(), &field.element)),
+        ));
+    }
+    drift.extend(compare_definitions(
+        "fieldType",
+        local
+            .field_types
+            .iter()
+            .map(|field_type| (field_type.name.as_str(), &field_type.element)),
+        live.field_types
+            .iter()
+            .map(|field_type| (field_type.name.as_str(), &field_type.element)),
+    ));
+    for field_type in &local.field_types {
+        let Some(live_type) = live.field_type(&field_type.name) else {
+            continue;
+        };
+        let chains = |analyzers: &[Analyzer]| -> Vec<String> {
+            analyzers.iter().map(analyzer_chain).collect()
+        };
+        if chains(&field_type.analyzers) != chains(&live_type.analyzers) {
+            drift.push(format!(
+                "The fieldType '{}' has other analyzers locally than in the live schema.",
+                field_type.name
+            ));
+        }
+    }
+    let copy_fields = |schema: &Schema| -> Vec<(String, String)> {
+        let mut pairs: Vec<(String, String)> = schema
+            .copy_fields
+            .iter()
+            .map(|copy_field| (copy_field.source.clone(), copy_field.dest.clone()))
+            .collect();
+        pairs.sort();
+        pairs
+    };
+    let (local_copies, live_copies) = (copy_fields(local), copy_fields(live));
+    for (pairs, other, place) in [
+        (&local_copies, &live_copies, "local"),
+        (&live_copies, &local_copies, "live"),
+    ] {
+        for (source, dest) in pairs.iter().filter(|pair| !other.contains(pair)) {
+            drift.push(format!(
+                "The copyField from '{}' to '{}' is only in the {} schema.",
+                source, dest, place
+            ));
+        }
+    }
+    drift
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::drift::compare_schemas;
+    use crate::model::Schema;
+    use crate::tree::parse_document;
+    use std::io::Cursor;
+
+    fn schema(example: &str) -> Schema {
+        Schema::from_document(&parse_document(Cursor::new(example)).unwrap())
+    }
+
+    #[test]
+    fn test_compare_schemas() {
+        let local = schema(
+            r#"<schema version="1.6">
+  <uniqueKey>id</uniqueKey>
+  <field name="id" type="string" required="true" />
+  <field name="title" type="text" stored="true" />
+  <field name="legacy" type="string" />
+  <copyField source="title" dest="title_s" />
+  <fieldType name="string" class="solr.StrField" />
+  <fieldType name="text" class="solr.TextField">
+    <analyzer>
+      <tokenizer class="solr.StandardTokenizerFactory" />
+      <filter class="solr.LowerCaseFilterFactory" />
+    </analyzer>
+  </fieldType>
+</schema>"#,
+        );
+        let live = schema(
+            r#"<schema version="1.6">
+  <uniqueKey>id</uniqueKey>
+  <field name="id" required="true" type="string" />
+  <field name="title" type="text" stored="false" />
+  <field name="colour_s" type="string" />
+  <copyField source="title" dest="title_s" />
+  <fieldType name="string" class="solr.StrField" />
+  <fieldType name="text" class="solr.TextField">
+    <analyzer>
+      <tokenizer name="standard" />
+      <filter name="lowercase" />
+      <filter name="stop" />
+    </analyzer>
+  </fieldType>
+</schema>"#,
+        );
+        assert_eq!(
+            compare_schemas(&local, &live),
+            vec![
+                "The field 'legacy' is only in the local schema.",
+                "The field 'title' has stored \"true\" locally and \"false\" in the live schema.",
+                "The field 'colour_s' is only in the live schema.",
+                "The fieldType 'text' has other analyzers locally than in the live schema.",
+            ]
+        );
+        assert!(compare_schemas(&local, &local).is_empty());
+    }
+}
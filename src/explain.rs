@@ -0,0 +1,147 @@
+use crate::model::{Analyzer, Schema};
+use crate::schema::OPTIONAL_FIELD_PROPERTIES;
+
+/// Describes where a field comes from and how Solr sees it, or `None` if no field or dynamicField matches.
+pub fn explain_field(schema: &Schema, name: &str) -> Option<String> {
+    let field = schema.resolve_field(name)?;
+    let mut out = format!("field: {}\n", name);
+    if field.dynamic {
+        out.push_str(&format!("  matched by: dynamicField '{}'\n", field.name));
+    } else {
+        out.push_str(&format!("  matched by: field '{}'\n", field.name));
+    }
+
+    let Some(field_type) = schema.type_of(field) else {
+        out.push_str(&format!(
+            "  fieldType: {} (not defined in the schema)\n",
+            field.type_name
+        ));
+        return Some(out);
+    };
+    out.push_str(&format!(
+        "  fieldType: {} ({})\n",
+        field_type.name, field_type.class
+    ));
+
+    out.push_str("  properties:\n");
+    for property in OPTIONAL_FIELD_PROPERTIES {
+        if property == "default" {
+            continue;
+        }
+        let (value, source) = schema.resolve_property(field, property);
+        out.push_str(&format!("    {}={} ({})\n", property, value, source));
+    }
+    if let Some(default) = field.element.attribute("default") {
+        out.push_str(&format!("    default={} (field)\n", default));
+    }
+
+    for kind in ["index", "query"] {
+        match field_type.analyzer(kind) {
+            Some(analyzer) => {
+                out.push_str(&format!("  {} analyzer:\n", kind));
+                write_analyzer(&mut out, analyzer);
+            }
+            None => out.push_str(&format!("  {} analyzer: none\n", kind)),
+        }
+    }
+
+    let into: Vec<&str> = schema
+        .copy_fields_into(name)
+        .iter()
+        .map(|copy_field| copy_field.source.as_str())
+        .collect();
+    let from: Vec<String> = schema
+        .copy_fields_from(name)
+        .into_iter()
+        .map(|(_, dest)| dest)
+        .collect();
+    out.push_str(&format!("  copyField into: {}\n", list(&into)));
+    out.push_str(&format!("  copyField out of: {}\n", list(&from)));
+    Some(out)
+}
+
+fn write_analyzer(out: &mut String, analyzer: &Analyzer) {
+    if let Some(class) = &analyzer.class {
+        out.push_str(&format!("    class: {}\n", class));
+    }
+    for component in &analyzer.components {
+        let arguments: Vec<String> = component
+            .element
+            .attributes
+            .iter()
+            .filter(|attr| attr.name.local_name != "class" && attr.name.local_name != "name")
+            .map(|attr| format!("{}={}", attr.name.local_name, attr.value))
+            .collect();
+        out.push_str(&format!("    {}: {}", component.kind, component.class));
+        if !arguments.is_empty() {
+            out.push_str(&format!(" {}", arguments.join(" ")));
+        }
+        out.push('\n');
+    }
+}
+
+fn list<T: AsRef<str>>(names: &[T]) -> String {
+    if names.is_empty() {
+        return "none".to_string();
+    }
+    names
+        .iter()
+        .map(AsRef::as_ref)
+        .collect::<Vec<&str>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::explain::explain_field;
+    use crate::model::Schema;
+    use crate::tree::parse_document;
+    use std::io::Cursor;
+
+    const SCHEMA: &str = r#"<schema version="1.6">
+        <field name="title" type="string" />
+        <dynamicField name="*_t" type="text" stored="false" />
+        <fieldType name="string" class="solr.StrField" docValues="true" />
+        <fieldType name="text" class="solr.TextField" multiValued="true">
+            <analyzer type="index">
+                <tokenizer class="solr.StandardTokenizerFactory" />
+                <filter class="solr.StopFilterFactory" words="stopwords.txt" />
+            </analyzer>
+            <analyzer type="query">
+                <tokenizer name="standard" />
+            </analyzer>
+        </fieldType>
+        <copyField source="title" dest="title_t" />
+        <copyField source="*_t" dest="*_s" />
+    </schema>"#;
+
+    fn schema() -> Schema {
+        Schema::from_document(&parse_document(Cursor::new(SCHEMA)).unwrap())
+    }
+
+    #[test]
+    fn test_explain_dynamic_field() {
+        let explained = explain_field(&schema(), "title_t").unwrap();
+        assert!(explained.contains("  matched by: dynamicField '*_t'\n"));
+        assert!(explained.contains("  fieldType: text (solr.TextField)\n"));
+        assert!(explained.contains("    stored=false (field)\n"));
+        assert!(explained.contains("    multiValued=true (fieldType)\n"));
+        assert!(explained.contains("    indexed=true (default)\n"));
+        assert!(explained.contains(
+            "  index analyzer:\n    tokenizer: solr.StandardTokenizerFactory\n    filter: solr.StopFilterFactory words=stopwords.txt\n"
+        ));
+        assert!(explained.contains("  query analyzer:\n    tokenizer: standard\n"));
+        assert!(explained.contains("  copyField into: title\n"));
+        assert!(explained.contains("  copyField out of: title_s\n"));
+    }
+
+    #[test]
+    fn test_explain_field() {
+        let explained = explain_field(&schema(), "title").unwrap();
+        assert!(explained.contains("  matched by: field 'title'\n"));
+        assert!(explained.contains("    docValues=true (fieldType)\n"));
+        assert!(explained.contains("  index analyzer: none\n"));
+        assert!(explained.contains("  copyField out of: title_t\n"));
+        assert!(explain_field(&schema(), "body").is_none());
+    }
+}
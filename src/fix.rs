@@ -0,0 +1,247 @@
+use xml::common::TextPosition;
+
+use crate::format::escape;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Edit {
+    SetAttribute { name: String, value: String },
+    RemoveAttribute { name: String },
+    RemoveElement,
+}
+
+/// A mechanical change to one element of the schema, located by the positions of its tags.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Fix {
+    pub start: TextPosition,
+    pub end: TextPosition,
+    pub edit: Edit,
+}
+
+/// Applies the fixes as plain text replacements so that everything else in the file is kept as is.
+/// Returns the fixed text and how many fixes were applied.
+pub fn apply_fixes(source: &str, fixes: &[Fix]) -> (String, usize) {
+    let mut replacements: Vec<(usize, usize, String)> =
+        fixes.iter().map(|fix| replacement(source, fix)).collect();
+    replacements.sort_by_key(|(start, end, _)| (*start, *end));
+
+    let mut fixed = source.to_string();
+    let mut applied_from = usize::MAX;
+    let mut applied = 0;
+    for (start, end, text) in replacements.into_iter().rev() {
+        // a fix that overlaps one applied already is dropped
+        if end > applied_from {
+            continue;
+        }
+        fixed.replace_range(start..end, &text);
+        applied_from = start;
+        applied += 1;
+    }
+    (fixed, applied)
+}
+
+fn replacement(source: &str, fix: &Fix) -> (usize, usize, String) {
+    let start = tag_start(source, offset(source, fix.start));
+    match &fix.edit {
+        Edit::SetAttribute { name, value } => {
+            let (attributes, attributes_end) = scan_attributes(source, start);
+            let value = escape(value, true);
+            match attributes.iter().find(|(attribute, ..)| attribute == name) {
+                Some((_, _, value_start, value_end)) => (*value_start, *value_end, value),
+                None => (
+                    attributes_end,
+                    attributes_end,
+                    format!(" {}=\"{}\"", name, value),
+                ),
+            }
+        }
+        Edit::RemoveAttribute { name } => {
+            let (attributes, _) = scan_attributes(source, start);
+            match attributes.iter().find(|(attribute, ..)| attribute == name) {
+                Some((_, name_start, _, value_end)) => {
+                    let name_start = source[..*name_start].trim_end().len();
+                    (name_start, value_end + 1, String::new())
+                }
+                None => (start, start, String::new()),
+            }
+        }
+        Edit::RemoveElement => {
+            let end_tag = if fix.end == fix.start {
+                start
+            } else {
+                tag_start(source, offset(source, fix.end))
+            };
+            let mut end = end_tag + tag_length(&source[end_tag..]);
+            let mut start = start;
+            // remove the whole line when the element is the only thing on it
+            let line_start = source[..start].rfind('\n').map_or(0, |i| i + 1);
+            let rest = &source[end..];
+            let line_end = rest.find('\n').map_or(rest.len(), |i| i + 1);
+            if source[line_start..start].trim().is_empty() && rest[..line_end].trim().is_empty() {
+                start = line_start;
+                end += line_end;
+            }
+            (start, end, String::new())
+        }
+    }
+}
+
+// xml-rs reports 0-based rows and columns counted in characters
+fn offset(source: &str, position: TextPosition) -> usize {
+    let line_start: usize = source
+        .split_inclusive('\n')
+        .take(position.row as usize)
+        .map(str::len)
+        .sum();
+    source[line_start..]
+        .char_indices()
+        .nth(position.column as usize)
+        .map_or(source.len(), |(i, _)| line_start + i)
+}
+
+// the position of the root element points inside its start tag rather than at the '<'
+fn tag_start(source: &str, offset: usize) -> usize {
+    source[..(offset + 1).min(source.len())]
+        .rfind('<')
+        .unwrap_or(offset)
+}
+
+fn tag_length(tag: &str) -> usize {
+    let mut quote: Option<char> = None;
+    for (i, c) in tag.char_indices() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, '"') | (None, '\'') => quote = Some(c),
+            (None, '>') => return i + 1,
+            _ => {}
+        }
+    }
+    tag.len()
+}
+
+/// Returns each attribute name with where it starts and the byte range of its value,
+/// and where the last attribute ends.
+fn scan_attributes(source: &str, start: usize) -> (Vec<(String, usize, usize, usize)>, usize) {
+    let bytes = source.as_bytes();
+    let mut i = start + 1;
+    while i < bytes.len() && !bytes[i].is_ascii_whitespace() && bytes[i] != b'/' && bytes[i] != b'>'
+    {
+        i += 1;
+    }
+    let mut attributes_end = i;
+    let mut attributes = Vec::new();
+    loop {
+        while i < bytes.len() && bytes[i].is_ascii_whitespace() {
+            i += 1;
+        }
+        if i >= bytes.len() || bytes[i] == b'/' || bytes[i] == b'>' {
+            break;
+        }
+        let name_start = i;
+        while i < bytes.len() && bytes[i] != b'=' && !bytes[i].is_ascii_whitespace() {
+            i += 1;
+        }
+        let name = &source[name_start..i];
+        while i < bytes.len() && (bytes[i] == b'=' || bytes[i].is_ascii_whitespace()) {
+            i += 1;
+        }
+        let quote = bytes[i];
+        let value_start = i + 1;
+        let value_end = value_start + source[value_start..].find(quote as char).unwrap_or(0);
+        attributes.push((
+            name.rsplit(':').next().unwrap_or(name).to_string(),
+            name_start,
+            value_start,
+            value_end,
+        ));
+        i = value_end + 1;
+        attributes_end = i;
+    }
+    (attributes, attributes_end)
+}
+
+/// A line based unified diff of two versions of a file.
+pub fn unified_diff(path: &str, old: &str, new: &str) -> String {
+    const CONTEXT: usize = 3;
+    let old_lines: Vec<&str> = old.lines().collect();
+    let new_lines: Vec<&str> = new.lines().collect();
+    let prefix = old_lines
+        .iter()
+        .zip(&new_lines)
+        .take_while(|(a, b)| a == b)
+        .count();
+    let suffix = old_lines[prefix..]
+        .iter()
+        .rev()
+        .zip(new_lines[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let a = &old_lines[prefix..old_lines.len() - suffix];
+    let b = &new_lines[prefix..new_lines.len() - suffix];
+
+    // longest common subsequence over the lines that differ
+    let mut table = vec![vec![0u32; b.len() + 1]; a.len() + 1];
+    for i in (0..a.len()).rev() {
+        for j in (0..b.len()).rev() {
+            table[i][j] = if a[i] == b[j] {
+                table[i + 1][j + 1] + 1
+            } else {
+                table[i + 1][j].max(table[i][j + 1])
+            };
+        }
+    }
+    // (line in old, line in new, marker, text)
+    let mut ops: Vec<(usize, usize, char, &str)> = Vec::new();
+    for (i, line) in old_lines[..prefix].iter().enumerate() {
+        ops.push((i, i, ' ', line));
+    }
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() || j < b.len() {
+        if i < a.len() && j < b.len() && a[i] == b[j] {
+            ops.push((prefix + i, prefix + j, ' ', a[i]));
+            i += 1;
+            j += 1;
+        } else if i < a.len() && (j == b.len() || table[i + 1][j] >= table[i][j + 1]) {
+            ops.push((prefix + i, prefix + j, '-', a[i]));
+            i += 1;
+        } else {
+            ops.push((prefix + i, prefix + j, '+', b[j]));
+            j += 1;
+        }
+    }
+    for k in 0..suffix {
+        let line = old_lines[old_lines.len() - suffix + k];
+        ops.push((prefix + a.len() + k, prefix + b.len() + k, ' ', line));
+    }
+
+    let changed: Vec<usize> = (0..ops.len()).filter(|&k| ops[k].2 != ' ').collect();
+    if changed.is_empty() {
+        return String::new();
+    }
+    let mut out = format!("--- {}\n+++ {}\n", path, path);
+    let mut k = 0;
+    while k < changed.len() {
+        let first = changed[k].saturating_sub(CONTEXT);
+        let mut last = changed[k];
+        while k + 1 < changed.len() && changed[k + 1] <= last + 2 * CONTEXT + 1 {
+            k += 1;
+            last = changed[k];
+        }
+        let last = (last + CONTEXT).min(ops.len() - 1);
+        let hunk = &ops[first..=last];
+        let old_count = hunk.iter().filter(|op| op.2 != '+').count();
+        let new_count = hunk.iter().filter(|op| op.2 != '-').count();
+        out.push_str(&format!(
+            "@@ -{},{} +{},{} @@\n",
+            hunk[0].0 + 1,
+            old_count,
+            hunk[0].1 + 1,
+            new_count
+        ));
+        for (_, _, marker, line) in hunk {
+            out.push_str(&format!("{}{}\n", marker, line));
+        }
+        k += 1;
+    }
+    out
+}
@@ -0,0 +1,297 @@
+use std::io::Read;
+
+use crate::schema::{FIELD_TYPE_GENERAL_PROPERTIES, OPTIONAL_FIELD_PROPERTIES};
+use crate::tree::{parse_document, Element, Node};
+
+const INDENT: &str = "  ";
+
+const LEADING_ATTRIBUTES: [&str; 5] = ["name", "type", "class", "source", "dest"];
+
+// the order in which the direct children of <schema> are written when grouping is requested
+const GROUP_ORDER: [&str; 6] = [
+    "uniqueKey",
+    "similarity",
+    "fieldType",
+    "field",
+    "dynamicField",
+    "copyField",
+];
+
+pub fn format_schema<R: Read>(reader: R, group: bool) -> Result<String, xml::reader::Error> {
+    let mut document = parse_document(reader)?;
+    if group {
+        group_children(&mut document.root);
+    }
+    let mut out = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    for node in &document.prolog {
+        write_node(&mut out, node, 0);
+    }
+    write_element(&mut out, &document.root, 0);
+    for node in &document.epilog {
+        write_node(&mut out, node, 0);
+    }
+    Ok(out)
+}
+
+fn group_children(root: &mut Element) {
+    let rank = |node: &Node| match node {
+        Node::Element(element) => GROUP_ORDER
+            .iter()
+            .position(|name| *name == element.local_name())
+            .unwrap_or(GROUP_ORDER.len()),
+        _ => GROUP_ORDER.len(),
+    };
+    // comments travel with the element that follows them, so documentation stays in place
+    let mut groups: Vec<(usize, Vec<Node>)> = Vec::new();
+    let mut pending: Vec<Node> = Vec::new();
+    for node in root.children.drain(..) {
+        match node {
+            Node::Text(ref text) if text.trim().is_empty() => {}
+            Node::Comment(_) => pending.push(node),
+            _ => {
+                let node_rank = rank(&node);
+                pending.push(node);
+                groups.push((node_rank, std::mem::take(&mut pending)));
+            }
+        }
+    }
+    groups.sort_by_key(|(node_rank, _)| *node_rank);
+
+    let mut previous_rank = None;
+    for (node_rank, nodes) in groups {
+        if previous_rank.is_some() && previous_rank != Some(node_rank) {
+            root.children.push(Node::Text("\n\n".to_string()));
+        }
+        root.children.extend(nodes);
+        previous_rank = Some(node_rank);
+    }
+    root.children.extend(pending);
+}
+
+fn ordered_attributes(element: &Element) -> Vec<(String, &str)> {
+    let rank = |name: &str| {
+        LEADING_ATTRIBUTES
+            .iter()
+            .chain(FIELD_TYPE_GENERAL_PROPERTIES.iter())
+            .chain(OPTIONAL_FIELD_PROPERTIES.iter())
+            .position(|known| *known == name)
+            .unwrap_or(usize::MAX)
+    };
+    let mut attributes: Vec<(String, &str)> = element
+        .attributes
+        .iter()
+        .map(|attr| (attr.name.borrow().to_repr(), attr.value.as_str()))
+        .collect();
+    // a stable sort keeps unknown attributes in their original order
+    attributes.sort_by_key(|(name, _)| rank(name));
+    attributes
+}
+
+fn write_node(out: &mut String, node: &Node, depth: usize) {
+    match node {
+        Node::Element(element) => write_element(out, element, depth),
+        Node::Comment(comment) => {
+            out.push_str(&INDENT.repeat(depth));
+            out.push_str(&format!("<!--{}-->\n", comment));
+        }
+        Node::Text(text) => {
+            if !text.trim().is_empty() {
+                out.push_str(&INDENT.repeat(depth));
+                out.push_str(&escape(text.trim(), false));
+                out.push('\n');
+            }
+        }
+        Node::ProcessingInstruction(name, data) => {
+            out.push_str(&INDENT.repeat(depth));
+            match data {
+                Some(data) => out.push_str(&format!("<?{} {}?>\n", name, data)),
+                None => out.push_str(&format!("<?{}?>\n", name)),
+            }
+        }
+    }
+}
+
+fn write_element(out: &mut String, element: &Element, depth: usize) {
+    out.push_str(&INDENT.repeat(depth));
+    out.push('<');
+    out.push_str(&element.qualified_name());
+    for (prefix, uri) in &element.namespaces {
+        match prefix.as_str() {
+            "" => out.push_str(&format!(" xmlns=\"{}\"", escape(uri, true))),
+            prefix => out.push_str(&format!(" xmlns:{}=\"{}\"", prefix, escape(uri, true))),
+        }
+    }
+    for (name, value) in ordered_attributes(element) {
+        out.push_str(&format!(" {}=\"{}\"", name, escape(value, true)));
+    }
+
+    let only_text = element
+        .children
+        .iter()
+        .all(|node| matches!(node, Node::Text(_)));
+    if only_text {
+        let text = element.text();
+        if text.is_empty() {
+            out.push_str(" />\n");
+        } else {
+            out.push_str(&format!(
+                ">{}</{}>\n",
+                escape(&text, false),
+                element.qualified_name()
+            ));
+        }
+        return;
+    }
+
+    out.push_str(">\n");
+    let mut written = false;
+    for node in &element.children {
+        match node {
+            // keep a single empty line wherever the source separated nodes with one or more
+            Node::Text(text) if text.trim().is_empty() => {
+                if written && text.matches('\n').count() > 1 {
+                    out.push('\n');
+                    written = false;
+                }
+            }
+            _ => {
+                write_node(out, node, depth + 1);
+                written = true;
+            }
+        }
+    }
+    if out.ends_with("\n\n") {
+        out.pop();
+    }
+    out.push_str(&INDENT.repeat(depth));
+    out.push_str(&format!("</{}>\n", element.qualified_name()));
+}
+
+pub(crate) fn escape(value: &str, attribute: bool) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' if attribute => escaped.push_str("&quot;"),
+            '\n' if attribute => escaped.push_str("&#10;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::format::format_schema;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_format_attribute_order_and_indentation() {
+        let example = r#"<schema version="1.6">
+<field stored="true" type="id_unique" required="true" name="id"/>
+      <fieldType docValues="true" class="solr.StrField" name="id_unique"/>
+</schema>"#;
+        let formatted = format_schema(Cursor::new(example), false).unwrap();
+        assert_eq!(
+            formatted,
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<schema version="1.6">
+  <field name="id" type="id_unique" stored="true" required="true" />
+  <fieldType name="id_unique" class="solr.StrField" docValues="true" />
+</schema>
+"#
+        );
+    }
+
+    #[test]
+    fn test_format_keeps_comments_and_text() {
+        let example = r#"<schema version="1.6">
+    <!-- the unique key -->
+    <uniqueKey> id </uniqueKey>
+
+
+    <fieldType name="text" class="solr.TextField" positionIncrementGap="100">
+    <analyzer><tokenizer class="solr.PatternTokenizerFactory" pattern="&quot;\|" /></analyzer>
+    </fieldType>
+</schema>"#;
+        let formatted = format_schema(Cursor::new(example), false).unwrap();
+        assert_eq!(
+            formatted,
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<schema version="1.6">
+  <!-- the unique key -->
+  <uniqueKey>id</uniqueKey>
+
+  <fieldType name="text" class="solr.TextField" positionIncrementGap="100">
+    <analyzer>
+      <tokenizer class="solr.PatternTokenizerFactory" pattern="&quot;\|" />
+    </analyzer>
+  </fieldType>
+</schema>
+"#
+        );
+    }
+
+    #[test]
+    fn test_format_groups_elements_by_kind() {
+        let example = r#"<schema version="1.6">
+  <field name="id" type="string" />
+  <!-- strings -->
+  <fieldType name="string" class="solr.StrField" />
+  <copyField source="id" dest="id_copy" />
+  <field name="id_copy" type="string" />
+  <uniqueKey>id</uniqueKey>
+</schema>"#;
+        let formatted = format_schema(Cursor::new(example), true).unwrap();
+        assert_eq!(
+            formatted,
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<schema version="1.6">
+  <uniqueKey>id</uniqueKey>
+
+  <!-- strings -->
+  <fieldType name="string" class="solr.StrField" />
+
+  <field name="id" type="string" />
+  <field name="id_copy" type="string" />
+
+  <copyField source="id" dest="id_copy" />
+</schema>
+"#
+        );
+    }
+
+    #[test]
+    fn test_format_keeps_namespaces() {
+        let example = r#"<schema xmlns:xi="http://www.w3.org/2001/XInclude" version="1.6" name="books">
+  <xi:include href="fields.xml" xi:parse="xml" xml:lang="en">
+    <xi:fallback><field type="string" name="id" /></xi:fallback>
+  </xi:include>
+</schema>"#;
+        let formatted = format_schema(Cursor::new(example), false).unwrap();
+        assert_eq!(
+            formatted,
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<schema xmlns:xi="http://www.w3.org/2001/XInclude" name="books" version="1.6">
+  <xi:include href="fields.xml" xi:parse="xml" xml:lang="en">
+    <xi:fallback>
+      <field name="id" type="string" />
+    </xi:fallback>
+  </xi:include>
+</schema>
+"#
+        );
+        let twice = format_schema(Cursor::new(formatted.as_str()), false).unwrap();
+        assert_eq!(formatted, twice);
+    }
+
+    #[test]
+    fn test_format_is_idempotent() {
+        let example = include_str!("../examples/managed-schema.xml");
+        let once = format_schema(Cursor::new(example), true).unwrap();
+        let twice = format_schema(Cursor::new(once.as_str()), true).unwrap();
+        assert_eq!(once, twice);
+    }
+}
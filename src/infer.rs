@@ -0,0 +1,274 @@
+use crate::documents::{DocumentValue, SampleDocument};
+use crate::format::{escape, format_schema};
+use crate::values::is_date;
+
+// the fieldTypes an inferred schema may use, by the name fields refer to
+const INFERRED_FIELD_TYPES: [(&str, &str); 6] = [
+    (
+        "boolean",
+        r#"<fieldType name="boolean" class="solr.BoolField" sortMissingLast="true" />"#,
+    ),
+    (
+        "plong",
+        r#"<fieldType name="plong" class="solr.LongPointField" docValues="true" />"#,
+    ),
+    (
+        "pdouble",
+        r#"<fieldType name="pdouble" class="solr.DoublePointField" docValues="true" />"#,
+    ),
+    (
+        "pdate",
+        r#"<fieldType name="pdate" class="solr.DatePointField" docValues="true" />"#,
+    ),
+    (
+        "string",
+        r#"<fieldType name="string" class="solr.StrField" sortMissingLast="true" docValues="true" />"#,
+    ),
+    (
+        "text_general",
+        r#"<fieldType name="text_general" class="solr.TextField" positionIncrementGap="100">
+  <analyzer>
+    <tokenizer class="solr.StandardTokenizerFactory" />
+    <filter class="solr.LowerCaseFilterFactory" />
+  </analyzer>
+</fieldType>"#,
+    ),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, clap::ValueEnum)]
+pub enum TextDefault {
+    String,
+    Text,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Inferred {
+    Bool,
+    Long,
+    Double,
+    Date,
+    Text,
+}
+
+impl Inferred {
+    fn of(value: &str) -> Inferred {
+        if value == "true" || value == "false" {
+            Inferred::Bool
+        } else if value.parse::<i64>().is_ok() {
+            Inferred::Long
+        } else if value.parse::<f64>().is_ok()
+            && value.chars().all(|c| "0123456789.-+eE".contains(c))
+        {
+            Inferred::Double
+        } else if is_date(value) && !value.starts_with("NOW") {
+            Inferred::Date
+        } else {
+            Inferred::Text
+        }
+    }
+
+    // the narrowest type that holds the values of both
+    fn merge(self, other: Inferred) -> Inferred {
+        match (self, other) {
+            (a, b) if a == b => a,
+            (Inferred::Long, Inferred::Double) | (Inferred::Double, Inferred::Long) => {
+                Inferred::Double
+            }
+            _ => Inferred::Text,
+        }
+    }
+
+    fn type_name(self, text_default: TextDefault) -> &'static str {
+        match (self, text_default) {
+            (Inferred::Bool, _) => "boolean",
+            (Inferred::Long, _) => "plong",
+            (Inferred::Double, _) => "pdouble",
+            (Inferred::Date, _) => "pdate",
+            (Inferred::Text, TextDefault::String) => "string",
+            (Inferred::Text, TextDefault::Text) => "text_general",
+        }
+    }
+}
+
+#[derive(Debug)]
+struct InferredField {
+    name: String,
+    inferred: Inferred,
+    multi_valued: bool,
+}
+
+fn collect_fields(
+    documents: &[SampleDocument],
+    fields: &mut Vec<InferredField>,
+    nested: &mut bool,
+) {
+    for document in documents {
+        for (name, value) in &document.fields {
+            let (values, multi_valued) = match value {
+                DocumentValue::Single(value) => (vec![value.as_str()], false),
+                DocumentValue::Multiple(values) => {
+                    (values.iter().map(String::as_str).collect(), true)
+                }
+                DocumentValue::Children(children) => {
+                    *nested = true;
+                    collect_fields(children, fields, nested);
+                    continue;
+                }
+            };
+            let Some(inferred) = values
+                .iter()
+                .map(|value| Inferred::of(value))
+                .reduce(Inferred::merge)
+            else {
+                continue;
+            };
+            match fields.iter_mut().find(|field| &field.name == name) {
+                Some(field) => {
+                    field.inferred = field.inferred.merge(inferred);
+                    field.multi_valued |= multi_valued;
+                }
+                None => fields.push(InferredField {
+                    name: name.clone(),
+                    inferred,
+                    multi_valued,
+                }),
+            }
+        }
+    }
+}
+
+/// Writes a managed-schema for the documents, guessing the type of each field from its values.
+pub fn infer_schema(
+    documents: &[SampleDocument],
+    text_default: TextDefault,
+    copy_to_text: bool,
+) -> String {
+    let mut fields: Vec<InferredField> = Vec::new();
+    let mut nested = false;
+    collect_fields(documents, &mut fields, &mut nested);
+
+    let mut xml = String::from("<schema name=\"inferred\" version=\"1.6\">\n");
+    let mut used_types: Vec<&str> = vec!["plong"];
+    if fields.iter().any(|field| field.name == "id") {
+        xml.push_str("<uniqueKey>id</uniqueKey>\n");
+    }
+    xml.push_str(r#"<field name="_version_" type="plong" indexed="false" stored="false" />"#);
+    if nested {
+        xml.push_str(r#"<field name="_root_" type="string" indexed="true" stored="false" docValues="false" />"#);
+        used_types.push("string");
+    }
+    let mut copy_fields = Vec::new();
+    for field in &fields {
+        let type_name = if field.name == "id" {
+            "string"
+        } else {
+            field.inferred.type_name(text_default)
+        };
+        used_types.push(type_name);
+        let mut properties = String::new();
+        if field.name == "id" {
+            properties.push_str(r#" required="true""#);
+        }
+        if field.multi_valued {
+            properties.push_str(r#" multiValued="true""#);
+        }
+        xml.push_str(&format!(
+            "<field name=\"{}\" type=\"{}\"{} />\n",
+            escape(&field.name, true),
+            type_name,
+            properties
+        ));
+        if copy_to_text && field.name != "id" && field.inferred == Inferred::Text {
+            copy_fields.push(format!(
+                "<copyField source=\"{}\" dest=\"_text_\" />\n",
+                escape(&field.name, true)
+            ));
+        }
+    }
+    if copy_to_text {
+        used_types.push("text_general");
+        xml.push_str(r#"<field name="_text_" type="text_general" multiValued="true" indexed="true" stored="false" />"#);
+        copy_fields
+            .iter()
+            .for_each(|copy_field| xml.push_str(copy_field));
+    }
+    for (name, definition) in INFERRED_FIELD_TYPES {
+        if used_types.contains(&name) {
+            xml.push_str(definition);
+            xml.push('\n');
+        }
+    }
+    xml.push_str("</schema>\n");
+    format_schema(xml.as_bytes(), false).expect("the inferred schema is well-formed")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::config::Config;
+    use crate::documents::{json_document, SampleDocument};
+    use crate::infer::{infer_schema, TextDefault};
+    use crate::resources::Resources;
+    use crate::rules::check_rules;
+    use crate::schema_operations;
+    use crate::tree::parse_document;
+    use std::io::Cursor;
+
+    fn documents(lines: &[&str]) -> Vec<SampleDocument> {
+        lines
+            .iter()
+            .map(|line| json_document(&serde_json::from_str(line).unwrap()).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_infer_field_types() {
+        let documents = documents(&[
+            r#"{"id": "1", "open": true, "count": 3, "score": 1, "published": "2024-05-01T00:00:00Z", "title": "Solr", "tags": ["a"]}"#,
+            r#"{"id": "2", "open": false, "count": 4, "score": 1.5, "published": "2024-05-02T00:00:00Z", "title": "Lucene"}"#,
+        ]);
+        assert_eq!(
+            infer_schema(&documents, TextDefault::String, false),
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<schema name="inferred" version="1.6">
+  <uniqueKey>id</uniqueKey>
+  <field name="_version_" type="plong" indexed="false" stored="false" />
+  <field name="count" type="plong" />
+  <field name="id" type="string" required="true" />
+  <field name="open" type="boolean" />
+  <field name="published" type="pdate" />
+  <field name="score" type="pdouble" />
+  <field name="tags" type="string" multiValued="true" />
+  <field name="title" type="string" />
+  <fieldType name="boolean" class="solr.BoolField" sortMissingLast="true" />
+  <fieldType name="plong" class="solr.LongPointField" docValues="true" />
+  <fieldType name="pdouble" class="solr.DoublePointField" docValues="true" />
+  <fieldType name="pdate" class="solr.DatePointField" docValues="true" />
+  <fieldType name="string" class="solr.StrField" docValues="true" sortMissingLast="true" />
+</schema>
+"#
+        );
+    }
+
+    #[test]
+    fn test_infer_writes_only_used_field_types() {
+        let documents = documents(&[r#"{"count": 3, "title": "Solr"}"#]);
+        let schema = infer_schema(&documents, TextDefault::Text, false);
+        assert!(schema.contains(r#"<fieldType name="text_general""#));
+        assert!(!schema.contains(r#"<fieldType name="string""#));
+    }
+
+    #[test]
+    fn test_inferred_schema_passes_validation() {
+        let documents = documents(&[
+            r#"{"id": "1", "title": "Solr in Action", "mixed": 1, "children": [{"id": "1.1", "body": "x"}]}"#,
+            r#"{"id": "2", "title": "Lucene", "mixed": "one"}"#,
+        ]);
+        let schema = infer_schema(&documents, TextDefault::Text, true);
+        assert!(schema.contains(r#"<field name="mixed" type="text_general" />"#));
+        assert!(schema.contains(r#"<copyField source="title" dest="_text_" />"#));
+        assert!(schema.contains(r#"<field name="_root_" type="string""#));
+        schema_operations(Cursor::new(schema.as_str()));
+        let document = parse_document(Cursor::new(schema.as_str())).unwrap();
+        assert!(check_rules(&document, &Config::default(), &Resources::default()).is_empty());
+    }
+}
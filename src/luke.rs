@@ -0,0 +1,154 @@
+use serde_json::Value;
+
+use crate::model::Schema;
+use crate::rules::{Finding, Severity};
+
+// the characters of the Luke `index` flags for the properties a schema declares
+const INDEX_FLAGS: [(char, &str); 3] = [('I', "indexed"), ('S', "stored"), ('O', "omitNorms")];
+
+// the characters of the Luke `schema` flags for the properties that the index flags, read
+// from the fields of a sample document, don't tell
+const SCHEMA_FLAGS: [(char, &str); 2] = [('D', "docValues"), ('M', "multiValued")];
+
+// the index flags of a field that Luke's sample document doesn't store, so none are known
+// but that it isn't stored
+const UNSTORED_FIELD: &str = "(unstored field)";
+
+fn finding(severity: Severity, message: String) -> Finding {
+    Finding {
+        rule: "luke",
+        severity,
+        message,
+        fixes: Vec::new(),
+    }
+}
+
+/// Compares the schema with the `fields` of an `/admin/luke` response: the index fields the
+/// schema doesn't cover, the schema fields without documents, and the types and index flags the
+/// running core reports for a field that differ from the declared ones.
+pub fn compare_luke(schema: &Schema, luke: &Value) -> Result<Vec<Finding>, String> {
+    let Some(index_fields) = luke["fields"].as_object() else {
+        return Err("The Luke response has no 'fields' object.".to_string());
+    };
+    let mut findings = Vec::new();
+    for (name, info) in index_fields {
+        let Some(field) = schema.resolve_field(name) else {
+            findings.push(finding(
+                Severity::Error,
+                format!(
+                    "The index has a field '{}' that no field or dynamicField of the schema covers.",
+                    name
+                ),
+            ));
+            continue;
+        };
+        if let Some(type_name) = info["type"].as_str() {
+            if type_name != field.type_name {
+                findings.push(finding(
+                    Severity::Warning,
+                    format!(
+                        "The field '{}' is declared with type '{}' but the index reports '{}'.",
+                        name, field.type_name, type_name
+                    ),
+                ));
+            }
+        }
+        let mut reported: Vec<(&str, bool, &str)> = Vec::new();
+        match info["index"].as_str() {
+            Some(UNSTORED_FIELD) => reported.push(("stored", false, "the index")),
+            Some(flags) => reported.extend(
+                INDEX_FLAGS
+                    .iter()
+                    .map(|(flag, property)| (*property, flags.contains(*flag), "the index")),
+            ),
+            None => {}
+        }
+        if let Some(flags) = info["schema"].as_str() {
+            reported.extend(
+                SCHEMA_FLAGS.iter().map(|(flag, property)| {
+                    (*property, flags.contains(*flag), "the core's schema")
+                }),
+            );
+        }
+        for (property, reported, source) in reported {
+            let declared = schema.property(field, property);
+            if declared != reported {
+                findings.push(finding(
+                    Severity::Warning,
+                    format!(
+                        "The field '{}' is declared with {}=\"{}\" but {} reports {}=\"{}\".",
+                        name, property, declared, source, property, reported
+                    ),
+                ));
+            }
+        }
+    }
+    for field in &schema.fields {
+        let populated = index_fields
+            .get(&field.name)
+            .is_some_and(|info| info["docs"].as_u64() != Some(0));
+        if !populated {
+            findings.push(finding(
+                Severity::Warning,
+                format!(
+                    "The field '{}' is never populated in the index.",
+                    field.name
+                ),
+            ));
+        }
+    }
+    Ok(findings)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::luke::compare_luke;
+    use crate::model::Schema;
+    use crate::tree::parse_document;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_compare_luke() {
+        let schema = Schema::from_document(
+            &parse_document(Cursor::new(
+                r#"<schema version="1.6">
+  <field name="id" type="string" />
+  <field name="title" type="text" />
+  <field name="summary" type="text" />
+  <field name="body" type="text" />
+  <dynamicField name="*_s" type="string" />
+  <fieldType name="string" class="solr.StrField" omitNorms="true" />
+  <fieldType name="text" class="solr.TextField" />
+</schema>"#,
+            ))
+            .unwrap(),
+        );
+        let luke = serde_json::json!({
+            "fields": {
+                "body": {"type": "text", "schema": "ITS-U------------", "index": "(unstored field)", "docs": 8},
+                "id": {"type": "string", "schema": "I-S-U-----OF-----", "index": "I-S-------OF-----", "docs": 12},
+                "title": {"type": "text_en", "schema": "ITS-UM-----------", "index": "ITS--------------", "docs": 10},
+                "colour_s": {"type": "string", "schema": "I-SDU-----OF-----", "index": "I-SD------OF-----", "docs": 4},
+                "legacy": {"type": "string", "schema": "I-S-U-----OF-----", "index": "I-S-------OF-----", "docs": 2},
+                "summary": {"type": "text", "schema": "ITS-U------------", "docs": 0}
+            }
+        });
+        let findings: Vec<String> = compare_luke(&schema, &luke)
+            .unwrap()
+            .into_iter()
+            .map(|finding| finding.to_string())
+            .collect();
+        assert_eq!(
+            findings,
+            vec![
+                "warning[luke]: The field 'body' is declared with stored=\"true\" but the index reports stored=\"false\".",
+                "warning[luke]: The field 'colour_s' is declared with docValues=\"false\" but the core's schema reports docValues=\"true\".",
+                "error[luke]: The index has a field 'legacy' that no field or dynamicField of the schema covers.",
+                "warning[luke]: The field 'title' is declared with type 'text' but the index reports 'text_en'.",
+                "warning[luke]: The field 'title' is declared with multiValued=\"false\" but the core's schema reports multiValued=\"true\".",
+                "warning[luke]: The field 'summary' is never populated in the index.",
+            ]
+        );
+        assert!(compare_luke(&schema, &serde_json::json!({})).is_err());
+    }
+}
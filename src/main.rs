@@ -1,31 +1,501 @@
+use crate::audit::QueryAudit;
+use crate::config::Config;
+use crate::configset::{check_configset, find_schema_file};
+use crate::documents::{check_documents, check_unused_dynamic_fields, read_documents};
+use crate::drift::compare_schemas;
+use crate::explain::explain_field;
+use crate::fix::unified_diff;
+use crate::format::format_schema;
+use crate::infer::{infer_schema, TextDefault};
+use crate::luke::compare_luke;
+use crate::matrix::{feature_matrix, format_matrix, MatrixFormat};
+use crate::model::{matches_pattern, Schema};
+use crate::package::{packaged_files, read_files, write_package};
+use crate::readiness::{atomic_update_readiness, Analysis};
+use crate::remote::SolrClient;
+use crate::resources::Resources;
+use crate::rules::{check_rules, fix_schema, Finding, Severity};
 use crate::schema::schema_parser;
-use clap::Parser;
-use std::io::BufReader;
-use std::{collections::HashMap, fs::File};
+use crate::tree::parse_document;
+use clap::{Parser, Subcommand};
+use std::io::{BufReader, Read, Seek};
+use std::path::PathBuf;
+use std::{collections::HashMap, fs};
 use xml::reader::{EventReader, XmlEvent};
 
+mod analysis;
+mod audit;
+mod classes;
+mod config;
+mod configset;
+mod documents;
+mod drift;
+mod explain;
+mod fix;
+mod format;
+mod infer;
+mod luke;
+mod matrix;
+mod model;
+mod package;
+mod readiness;
+mod remote;
+mod resources;
+mod rules;
 mod schema;
+mod solrconfig;
+mod tree;
+mod values;
 #[derive(Parser, Debug)]
 #[command(name = "schemeless")]
 #[command(bin_name = "schemeless")]
 #[command(version = "0.0.1")]
 #[command(author, version, about, long_about = None)]
 #[command(next_line_help = true)]
-#[group(required = true)]
+#[command(subcommand_negates_reqs = true)]
 struct SchemaArgs {
-    #[arg(id="file", short='f', value_parser = clap::value_parser!(String))]
-    file: String,
+    #[arg(id="file", short='f', required_unless_present = "url", conflicts_with = "url", value_parser = clap::value_parser!(String))]
+    file: Option<String>,
+    #[command(flatten)]
+    remote: RemoteArgs,
+    /// Rewrite the file with the fixes of the mechanically fixable findings
+    #[arg(long, conflicts_with = "url")]
+    fix: bool,
+    /// Print the fixes as a diff instead of rewriting the file
+    #[arg(long, requires = "fix")]
+    diff: bool,
+    /// A TOML file with the settings of the rules
+    #[arg(long, global = true)]
+    config: Option<String>,
+    #[command(subcommand)]
+    command: Option<SchemaCommand>,
 }
 
-fn main() -> std::io::Result<()> {
-    let args = SchemaArgs::parse();
-    let file = File::open(args.file)?;
-    let file: BufReader<&File> = BufReader::new(&file);
-    schema_operations(file);
+#[derive(clap::Args, Debug)]
+struct RemoteArgs {
+    /// Validate the live schema of a collection, e.g. http://localhost:8983/solr/books
+    #[arg(long)]
+    url: Option<String>,
+    /// The basic auth credentials as USER:PASSWORD
+    #[arg(long, requires = "url")]
+    user: Option<String>,
+    /// A PEM file with the certificates to trust instead of the system roots
+    #[arg(long, requires = "url")]
+    cacert: Option<String>,
+    /// Don't verify the TLS certificate of the server
+    #[arg(long, requires = "url")]
+    insecure: bool,
+    /// Show the drift between a local schema and the live schema instead of validating it
+    #[arg(long, requires = "url")]
+    compare_with: Option<String>,
+}
+
+#[derive(Subcommand, Debug)]
+enum SchemaCommand {
+    /// Rewrite a schema file in the canonical layout
+    Fmt {
+        file: String,
+        /// Exit with a non-zero status if the file is not formatted instead of rewriting it
+        #[arg(long)]
+        check: bool,
+        /// Group the schema elements by kind
+        #[arg(long)]
+        group: bool,
+    },
+    /// Validate JSON, JSONL, CSV or Solr XML documents against a schema
+    ValidateDocs {
+        schema: String,
+        #[arg(required = true)]
+        documents: Vec<String>,
+    },
+    /// Write a schema for sample JSON, JSONL, CSV or Solr XML documents
+    Infer {
+        #[arg(required = true)]
+        documents: Vec<String>,
+        /// The type of fields whose values are neither booleans, numbers nor dates
+        #[arg(long, value_enum, default_value_t = TextDefault::String)]
+        text_default: TextDefault,
+        /// Add a _text_ catch-all field and copy the textual fields into it
+        #[arg(long)]
+        copy_to_text: bool,
+        /// Write the schema to a file instead of the standard output
+        #[arg(short = 'o', long)]
+        output: Option<String>,
+    },
+    /// Validate a configset directory or zip: the schema and the files around it
+    Check {
+        /// The configset directory, its conf directory or a zip of it
+        directory: String,
+    },
+    /// Validate a configset and package it as a zip for the Configsets API
+    Package {
+        /// The configset directory, its conf directory or a zip of it
+        directory: String,
+        /// The zip file to write
+        #[arg(short = 'o', long)]
+        output: String,
+    },
+    /// Show the fully resolved definition of a field
+    Explain { schema: String, field: String },
+    /// Resolve the fields of the requests in Solr request logs and summarize their problems
+    AuditQueries {
+        schema: String,
+        #[arg(required = true)]
+        logs: Vec<String>,
+    },
+    /// Compare a schema with the fields of an /admin/luke JSON response
+    LukeCompare { schema: String, luke: String },
+    /// Show which sorting, faceting, grouping, export, highlighting and function query features
+    /// every field supports
+    Matrix {
+        schema: String,
+        #[arg(long, value_enum, default_value_t = MatrixFormat::Table)]
+        format: MatrixFormat,
+    },
+    /// Report which fields stand in the way of a Solr feature
+    Readiness {
+        #[arg(value_enum)]
+        analysis: Analysis,
+        schema: String,
+    },
+}
+
+fn main() {
+    if let Err(e) = run(SchemaArgs::parse()) {
+        eprintln!("Error: {}", e);
+        std::process::exit(1);
+    }
+}
+
+fn run(args: SchemaArgs) -> std::io::Result<()> {
+    let config = Config::load(args.config.as_deref())?;
+    match args.command {
+        Some(SchemaCommand::Fmt { file, check, group }) => fmt_operations(&file, check, group),
+        Some(SchemaCommand::ValidateDocs { schema, documents }) => {
+            validate_docs_operations(&schema, &documents)
+        }
+        Some(SchemaCommand::Infer {
+            documents,
+            text_default,
+            copy_to_text,
+            output,
+        }) => infer_operations(&documents, text_default, copy_to_text, output),
+        Some(SchemaCommand::Explain { schema, field }) => explain_operations(&schema, &field),
+        Some(SchemaCommand::AuditQueries { schema, logs }) => audit_operations(&schema, &logs),
+        Some(SchemaCommand::LukeCompare { schema, luke }) => luke_operations(&schema, &luke),
+        Some(SchemaCommand::Matrix { schema, format }) => matrix_operations(&schema, format),
+        Some(SchemaCommand::Readiness { analysis, schema }) => {
+            readiness_operations(analysis, &schema)
+        }
+        Some(SchemaCommand::Check { directory }) => check_operations(&directory, &config),
+        Some(SchemaCommand::Package { directory, output }) => {
+            package_operations(&directory, &output, &config)
+        }
+        None => {
+            if args.remote.url.is_some() {
+                return remote_operations(&args.remote, &config);
+            }
+            let file = args.file.expect("-f is required without a subcommand");
+            if args.fix {
+                return fix_operations(&file, args.diff, &config);
+            }
+            let content = fs::read_to_string(&file)?;
+            schema_operations(content.as_bytes());
+            rules_operations(&content, &config, &Resources::of_schema(&file))
+        }
+    }
+}
+
+fn remote_operations(remote: &RemoteArgs, config: &Config) -> std::io::Result<()> {
+    let url = remote.url.as_deref().expect("--url is given");
+    let client = SolrClient::new(
+        url,
+        remote.user.as_deref(),
+        remote.cacert.as_deref(),
+        remote.insecure,
+    )?;
+    let content = client.fetch_schema()?;
+    let Some(local) = &remote.compare_with else {
+        schema_operations(content.as_bytes());
+        return rules_operations(&content, config, &Resources::of_remote(client));
+    };
+    let live = parse_document(content.as_bytes())
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+    let drift = compare_schemas(&load_schema(local)?, &Schema::from_document(&live));
+    for difference in &drift {
+        println!("{}", difference);
+    }
+    if !drift.is_empty() {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+fn rules_operations(content: &str, config: &Config, resources: &Resources) -> std::io::Result<()> {
+    let document = parse_document(content.as_bytes())
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+    let mut findings = check_rules(&document, config, resources);
+    // a file that could not be fetched was not checked, which must not pass for a clean schema
+    findings.extend(resources.failures().into_iter().map(|message| Finding {
+        rule: "remote",
+        severity: Severity::Error,
+        message,
+        fixes: Vec::new(),
+    }));
+    report_findings(&findings);
+    Ok(())
+}
+
+fn report_findings(findings: &[Finding]) {
+    for finding in findings {
+        eprintln!("{}", finding);
+    }
+    if findings
+        .iter()
+        .any(|finding| finding.severity == Severity::Error)
+    {
+        std::process::exit(1);
+    }
+}
+
+fn fix_operations(file: &str, diff: bool, config: &Config) -> std::io::Result<()> {
+    let content = fs::read_to_string(file)?;
+    let resources = Resources::of_schema(file);
+    let (fixed, applied) = fix_schema(&content, config, &resources)
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+    if diff {
+        print!("{}", unified_diff(file, &content, &fixed));
+        return Ok(());
+    }
+    if fixed != content {
+        fs::write(file, &fixed)?;
+        println!("Applied {} fixes to '{}'.", applied, file);
+    }
+    schema_operations(fixed.as_bytes());
+    rules_operations(&fixed, config, &resources)
+}
+
+fn load_schema(file: &str) -> std::io::Result<Schema> {
+    let content = fs::read_to_string(file)?;
+    let document = parse_document(content.as_bytes())
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+    Ok(Schema::from_document(&document))
+}
+
+fn validate_docs_operations(schema: &str, files: &[String]) -> std::io::Result<()> {
+    let schema = load_schema(schema)?;
+    let mut all_documents = Vec::new();
+    let mut findings = Vec::new();
+    for file in files {
+        let documents = read_documents(file)?;
+        let file_findings = check_documents(&schema, &documents);
+        println!(
+            "Checked {} documents in '{}': {} problems.",
+            documents.len(),
+            file,
+            file_findings.len()
+        );
+        findings.extend(file_findings);
+        all_documents.extend(documents);
+    }
+    findings.extend(check_unused_dynamic_fields(&schema, &all_documents));
+    report_findings(&findings);
+    Ok(())
+}
+
+fn infer_operations(
+    files: &[String],
+    text_default: TextDefault,
+    copy_to_text: bool,
+    output: Option<String>,
+) -> std::io::Result<()> {
+    let mut documents = Vec::new();
+    for file in files {
+        documents.extend(read_documents(file)?);
+    }
+    let schema = infer_schema(&documents, text_default, copy_to_text);
+    match output {
+        Some(output) => fs::write(output, schema),
+        None => {
+            print!("{}", schema);
+            Ok(())
+        }
+    }
+}
+
+fn explain_operations(schema: &str, field: &str) -> std::io::Result<()> {
+    let schema = load_schema(schema)?;
+    match explain_field(&schema, field) {
+        Some(explained) => print!("{}", explained),
+        None => {
+            eprintln!(
+                "Could not find a field or dynamicField matching '{}'.",
+                field
+            );
+            std::process::exit(1);
+        }
+    }
+    Ok(())
+}
+
+fn audit_operations(schema: &str, logs: &[String]) -> std::io::Result<()> {
+    let schema = load_schema(schema)?;
+    let mut audit = QueryAudit::default();
+    for log in logs {
+        audit.add_log(&schema, BufReader::new(fs::File::open(log)?))?;
+    }
+    print!("{}", audit);
+    if audit.has_problems() {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+fn luke_operations(schema: &str, luke: &str) -> std::io::Result<()> {
+    let schema = load_schema(schema)?;
+    let invalid = |e: String| std::io::Error::new(std::io::ErrorKind::InvalidData, e);
+    let luke = serde_json::from_str(&fs::read_to_string(luke)?)
+        .map_err(|e: serde_json::Error| invalid(e.to_string()))?;
+    report_findings(&compare_luke(&schema, &luke).map_err(invalid)?);
+    Ok(())
+}
+
+fn matrix_operations(schema: &str, format: MatrixFormat) -> std::io::Result<()> {
+    let schema = load_schema(schema)?;
+    print!("{}", format_matrix(&feature_matrix(&schema), format)?);
     Ok(())
 }
 
+fn readiness_operations(analysis: Analysis, schema: &str) -> std::io::Result<()> {
+    let schema = load_schema(schema)?;
+    match analysis {
+        Analysis::AtomicUpdates => print!("{}", atomic_update_readiness(&schema)),
+    }
+    Ok(())
+}
+
+// the signature of a zip file that starts with a file entry
+const ZIP_SIGNATURE: &[u8] = b"PK\x03\x04";
+
+// the configset of a directory, its conf directory, or a zip
+fn configset_resources(path: &str) -> std::io::Result<Resources> {
+    let mut directory = PathBuf::from(path);
+    if directory.is_file() {
+        let mut signature = [0; 4];
+        let mut file = fs::File::open(&directory)?;
+        let read = file.read(&mut signature)?;
+        let is_zip = directory
+            .extension()
+            .is_some_and(|extension| extension.eq_ignore_ascii_case("zip"))
+            || signature[..read] == *ZIP_SIGNATURE;
+        if !is_zip {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!(
+                    "'{}' is neither a configset directory nor a zip of one.",
+                    path
+                ),
+            ));
+        }
+        file.rewind()?;
+        return Resources::from_zip(file);
+    }
+    if directory.join("conf").is_dir() {
+        directory.push("conf");
+    }
+    Ok(Resources::in_directory(&directory))
+}
+
+// the schema file Solr loads from the configset with the findings of the full validation
+fn validate_configset(
+    path: &str,
+    resources: &Resources,
+    config: &Config,
+) -> std::io::Result<(String, Vec<Finding>)> {
+    let (schema_file, mut findings) = find_schema_file(resources);
+    let Some(schema_file) = schema_file else {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::NotFound,
+            format!("Could not find the schema file Solr loads in '{}'.", path),
+        ));
+    };
+    let content = resources.read(&schema_file).ok_or_else(|| {
+        std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            format!("The '{}' is not UTF-8.", schema_file),
+        )
+    })?;
+    validate_schema_structure(content.as_bytes()).map_err(|message| {
+        std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            format!("The '{}' is invalid: {}", schema_file, message),
+        )
+    })?;
+    let document = parse_document(content.as_bytes())
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+    findings.extend(check_rules(&document, config, resources));
+    findings.extend(check_configset(resources));
+    Ok((schema_file, findings))
+}
+
+fn check_operations(path: &str, config: &Config) -> std::io::Result<()> {
+    let resources = configset_resources(path)?;
+    let (_, findings) = validate_configset(path, &resources, config)?;
+    report_findings(&findings);
+    Ok(())
+}
+
+fn package_operations(path: &str, output: &str, config: &Config) -> std::io::Result<()> {
+    let resources = configset_resources(path)?;
+    let (schema_file, mut findings) = validate_configset(path, &resources, config)?;
+    let (files, package_findings) = packaged_files(&resources, &schema_file)?;
+    findings.extend(package_findings);
+    for finding in &findings {
+        eprintln!("{}", finding);
+    }
+    if findings
+        .iter()
+        .any(|finding| finding.severity == Severity::Error)
+    {
+        eprintln!("Not packaging '{}' because of the errors above.", path);
+        std::process::exit(1);
+    }
+    // the zip is built in memory so that a failure leaves no partial output behind
+    let contents = read_files(&resources, &files)?;
+    let mut zip = std::io::Cursor::new(Vec::new());
+    write_package(&contents, &schema_file, &mut zip)?;
+    fs::write(output, zip.into_inner())?;
+    println!(
+        "Packaged {} files of '{}' into '{}'.",
+        files.len(),
+        path,
+        output
+    );
+    Ok(())
+}
+
+fn fmt_operations(file: &str, check: bool, group: bool) -> std::io::Result<()> {
+    let content = fs::read_to_string(file)?;
+    let formatted = format_schema(content.as_bytes(), group)
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+    if formatted == content {
+        return Ok(());
+    }
+    if check {
+        eprintln!("The file '{}' is not formatted.", file);
+        std::process::exit(1);
+    }
+    fs::write(file, formatted)
+}
+
+// the legacy validation of -f and --url, which stops at the first structural error
 fn schema_operations<R: std::io::Read>(reader: R) {
+    if let Err(message) = validate_schema_structure(reader) {
+        panic!("{}", message)
+    }
+}
+
+fn validate_schema_structure<R: std::io::Read>(reader: R) -> Result<(), String> {
     let buf_reader = BufReader::new(reader);
     let parser = EventReader::new(buf_reader);
 
@@ -45,17 +515,15 @@ fn schema_operations<R: std::io::Read>(reader: R) {
                     &mut field_types,
                     &name,
                     attributes,
-                );
+                )?;
                 let local_name = name.local_name.as_str();
                 if local_name == "uniqueKey" {
                     unique_key_exists = true;
                 }
             }
-            Ok(XmlEvent::Characters(ref data)) => {
-                if unique_key_exists {
-                    id_field = data.to_owned();
-                    unique_key_exists = false;
-                }
+            Ok(XmlEvent::Characters(ref data)) if unique_key_exists => {
+                id_field = data.to_owned();
+                unique_key_exists = false;
             }
             Err(e) => {
                 eprintln!("Error: {e}");
@@ -65,31 +533,46 @@ fn schema_operations<R: std::io::Read>(reader: R) {
         }
     }
     if !id_field.is_empty() && !names.contains(&format!("field:{}", &id_field)) {
-        panic!(
+        return Err(format!(
             "Could not found the field '{}' among the field types.",
             id_field
-        )
+        ));
     }
     for (key_field, value_field_type) in field_types.iter() {
         if !names.contains(&format!("fieldType:{}", value_field_type)) {
-            panic!(
+            return Err(format!(
                 "Could not find the type '{}' defined in '{}'",
                 value_field_type, key_field
-            )
+            ));
         }
     }
+    // copyFields may also name a dynamicField pattern or a field that one of the patterns matches
+    let dynamic_fields: Vec<&str> = names
+        .iter()
+        .filter_map(|name| name.strip_prefix("dynamicField:"))
+        .collect();
     for key in &copy_fields {
         let field_name = &format!("field:{}", key);
-        if names.contains(&field_name) {
+        if names.contains(field_name)
+            || dynamic_fields
+                .iter()
+                .any(|pattern| pattern == key || matches_pattern(pattern, key))
+        {
             continue;
         }
-        panic!("Could not find the field type '{}' in one copyField.", key)
+        return Err(format!(
+            "Could not find the field type '{}' in one copyField.",
+            key
+        ));
     }
+    Ok(())
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::schema_operations;
+    use crate::config::Config;
+    use crate::resources::Resources;
+    use crate::{schema_operations, validate_configset};
     use std::io::Cursor;
 
     #[test]
@@ -291,6 +774,21 @@ mod tests {
         schema_operations(cursor)
     }
 
+    #[test]
+    fn test_copy_field_to_dynamic_field() {
+        let example = r#"
+        <schema version="1.6">
+        <field name="title" type="string" stored="true" />
+        <dynamicField name="*_t" type="string" stored="true" />
+        <fieldType name="string" class="solr.StrField" sortMissingLast="true" docValues="true" />
+        <copyField source="title" dest="title_t" />
+        <copyField source="*_t" dest="title" />
+        </schema>
+        "#;
+        let cursor = Cursor::new(example);
+        schema_operations(cursor)
+    }
+
     #[test]
     #[should_panic(expected = "Found duplicate field names 'string'")]
     fn test_duplicate_value() {
@@ -344,4 +842,39 @@ mod tests {
         let cursor = Cursor::new(example);
         schema_operations(cursor)
     }
+
+    #[test]
+    fn test_term_vector_and_offset_properties() {
+        let example = r#"
+        <schema version="1.6">
+            <field name="body" type="string" termVectors="true" termPositions="true" termOffsets="true" />
+            <field name="title" type="string" storeOffsetsWithPositions="true" />
+            <fieldType name="string" class="solr.StrField" sortMissingLast="true" docValues="true" />
+        </schema>
+        "#;
+        let cursor = Cursor::new(example);
+        schema_operations(cursor)
+    }
+
+    #[test]
+    fn test_validate_configset_errors() {
+        let config = Config::default();
+        let error = validate_configset("books", &Resources::default(), &config).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Could not find the schema file Solr loads in 'books'."
+        );
+
+        let resources = Resources::default()
+            .with_file("solrconfig.xml", "<config />")
+            .with_file(
+                "managed-schema.xml",
+                r#"<schema version="1.6"><field name="id" type="missing" /></schema>"#,
+            );
+        let error = validate_configset("books", &resources, &config).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "The 'managed-schema.xml' is invalid: Could not find the type 'missing' defined in 'id'"
+        );
+    }
 }
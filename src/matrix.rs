@@ -0,0 +1,205 @@
+use serde_json::{Map, Value};
+
+use crate::model::{Field, FieldType, Schema};
+
+/// The Solr features the matrix has a column for, in column order.
+pub const FEATURES: [&str; 6] = ["sort", "facet", "group", "export", "highlight", "function"];
+
+// the classes function queries can read a number from
+const NUMERIC_CLASSES: [&str; 10] = [
+    "IntPointField",
+    "LongPointField",
+    "FloatPointField",
+    "DoublePointField",
+    "DatePointField",
+    "TrieIntField",
+    "TrieLongField",
+    "TrieFloatField",
+    "TrieDoubleField",
+    "TrieDateField",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, clap::ValueEnum)]
+pub enum MatrixFormat {
+    Table,
+    Csv,
+    Json,
+}
+
+/// A field or dynamicField with the features of `FEATURES` it supports.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Row {
+    pub field: String,
+    pub field_type: String,
+    pub supported: [bool; FEATURES.len()],
+}
+
+fn supported_features(schema: &Schema, field: &Field) -> [bool; FEATURES.len()] {
+    let property = |name: &str| schema.property(field, name);
+    let class_name = schema
+        .type_of(field)
+        .map(FieldType::class_name)
+        .unwrap_or_default();
+    let doc_values = property("docValues");
+    let uninverted = doc_values || property("indexed") && property("uninvertible");
+    let single_valued = !property("multiValued");
+    [
+        uninverted && single_valued,
+        uninverted,
+        uninverted && single_valued,
+        // /export and streaming expressions only read docValues
+        doc_values,
+        // the unified highlighter needs offsets, from the postings or the term vectors
+        property("stored") && (property("storeOffsetsWithPositions") || property("termVectors")),
+        doc_values && NUMERIC_CLASSES.contains(&class_name),
+    ]
+}
+
+/// The features every field and dynamicField supports with its effective properties and class.
+pub fn feature_matrix(schema: &Schema) -> Vec<Row> {
+    schema
+        .fields
+        .iter()
+        .chain(&schema.dynamic_fields)
+        .map(|field| Row {
+            field: field.name.clone(),
+            field_type: field.type_name.clone(),
+            supported: supported_features(schema, field),
+        })
+        .collect()
+}
+
+fn table(rows: &[Row]) -> String {
+    let mut lines = vec![["field", "type"]
+        .into_iter()
+        .chain(FEATURES)
+        .map(str::to_string)
+        .collect::<Vec<String>>()];
+    for row in rows {
+        let mut line = vec![row.field.clone(), row.field_type.clone()];
+        line.extend(
+            row.supported
+                .iter()
+                .map(|supported| if *supported { "yes" } else { "-" }.to_string()),
+        );
+        lines.push(line);
+    }
+    let widths: Vec<usize> = (0..lines[0].len())
+        .map(|column| {
+            lines
+                .iter()
+                .map(|line| line[column].chars().count())
+                .max()
+                .unwrap_or_default()
+        })
+        .collect();
+    let mut output = String::new();
+    for line in lines {
+        let cells: Vec<String> = line
+            .iter()
+            .zip(&widths)
+            .map(|(cell, width)| format!("{:width$}", cell, width = width))
+            .collect();
+        output.push_str(cells.join("  ").trim_end());
+        output.push('\n');
+    }
+    output
+}
+
+fn csv(rows: &[Row]) -> csv::Result<String> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    writer.write_record(["field", "type"].into_iter().chain(FEATURES))?;
+    for row in rows {
+        let supported = row.supported.map(|supported| supported.to_string());
+        writer.write_record(
+            [row.field.as_str(), row.field_type.as_str()]
+                .into_iter()
+                .chain(supported.iter().map(String::as_str)),
+        )?;
+    }
+    let bytes = writer
+        .into_inner()
+        .map_err(|e| csv::Error::from(e.into_error()))?;
+    Ok(String::from_utf8_lossy(&bytes).into_owned())
+}
+
+fn json(rows: &[Row]) -> String {
+    let rows: Vec<Value> = rows
+        .iter()
+        .map(|row| {
+            let mut object = Map::new();
+            object.insert("field".to_string(), Value::from(row.field.as_str()));
+            object.insert("type".to_string(), Value::from(row.field_type.as_str()));
+            for (feature, supported) in FEATURES.iter().zip(row.supported) {
+                object.insert(feature.to_string(), Value::from(supported));
+            }
+            Value::Object(object)
+        })
+        .collect();
+    let mut output = serde_json::to_string_pretty(&rows).unwrap_or_default();
+    output.push('\n');
+    output
+}
+
+/// Writes the matrix in the format.
+pub fn format_matrix(rows: &[Row], format: MatrixFormat) -> csv::Result<String> {
+    match format {
+        MatrixFormat::Table => Ok(table(rows)),
+        MatrixFormat::Csv => csv(rows),
+        MatrixFormat::Json => Ok(json(rows)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::matrix::{feature_matrix, format_matrix, MatrixFormat};
+    use crate::model::Schema;
+    use crate::tree::parse_document;
+    use std::io::Cursor;
+
+    const SCHEMA: &str = r#"<schema version="1.7">
+  <field name="id" type="string" />
+  <field name="tags" type="string" multiValued="true" />
+  <field name="body" type="text" storeOffsetsWithPositions="true" />
+  <field name="price" type="pfloat" />
+  <dynamicField name="*_raw" type="string" indexed="false" docValues="false" />
+  <fieldType name="string" class="solr.StrField" />
+  <fieldType name="text" class="solr.TextField" uninvertible="false" />
+  <fieldType name="pfloat" class="solr.FloatPointField" />
+</schema>"#;
+
+    fn matrix(format: MatrixFormat) -> String {
+        let schema = Schema::from_document(&parse_document(Cursor::new(SCHEMA)).unwrap());
+        format_matrix(&feature_matrix(&schema), format).unwrap()
+    }
+
+    #[test]
+    fn test_table() {
+        assert_eq!(
+            matrix(MatrixFormat::Table),
+            "field  type    sort  facet  group  export  highlight  function
+id     string  yes   yes    yes    yes     -          -
+tags   string  -     yes    -      yes     -          -
+body   text    -     -      -      -       yes        -
+price  pfloat  yes   yes    yes    yes     -          yes
+*_raw  string  -     -      -      -       -          -
+"
+        );
+    }
+
+    #[test]
+    fn test_csv_and_json() {
+        let csv = matrix(MatrixFormat::Csv);
+        assert_eq!(
+            csv.lines().take(2).collect::<Vec<&str>>(),
+            vec![
+                "field,type,sort,facet,group,export,highlight,function",
+                "id,string,true,true,true,true,false,false",
+            ]
+        );
+        let json: serde_json::Value = serde_json::from_str(&matrix(MatrixFormat::Json)).unwrap();
+        assert_eq!(json[3]["field"], "price");
+        assert_eq!(json[3]["function"], true);
+        assert_eq!(json[2]["highlight"], true);
+    }
+}
@@ -0,0 +1,363 @@
+use crate::tree::{Document, Element};
+
+// classes that index a single untokenized value and therefore support docValues
+const DOC_VALUES_CLASSES: [&str; 11] = [
+    "BoolField",
+    "DatePointField",
+    "DoublePointField",
+    "EnumFieldType",
+    "FloatPointField",
+    "IntPointField",
+    "LongPointField",
+    "SortableTextField",
+    "StrField",
+    "UUIDField",
+    "LatLonPointSpatialField",
+];
+
+/// A charFilter, tokenizer or filter of an analyzer chain.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Component {
+    pub kind: String,
+    /// The `class` attribute, or the SPI `name` such as `lowercase`.
+    pub class: String,
+    pub element: Element,
+}
+
+impl Component {
+    /// Whether the component is made by the factory, e.g. `LowerCaseFilterFactory`, whether
+    /// the class or the SPI name (`lowercase`) is given.
+    pub fn is(&self, factory: &str) -> bool {
+        self.factory().eq_ignore_ascii_case(factory)
+    }
+
+    /// The factory class without its package, e.g. `LowerCaseFilterFactory`; for an SPI name
+    /// only the case of the first letter is known, e.g. `LowercaseFilterFactory`.
+    pub fn factory(&self) -> String {
+        let class = self.class.rsplit('.').next().unwrap_or(&self.class);
+        if class.ends_with("Factory") {
+            return class.to_string();
+        }
+        let suffix = match self.kind.as_str() {
+            "charFilter" => "CharFilterFactory",
+            "tokenizer" => "TokenizerFactory",
+            _ => "FilterFactory",
+        };
+        let mut chars = class.chars();
+        let first = chars.next().map(|c| c.to_ascii_uppercase());
+        first
+            .into_iter()
+            .chain(chars)
+            .chain(suffix.chars())
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Analyzer {
+    /// `index`, `query` or `multiterm`; an analyzer without a type is used for indexing and querying.
+    pub kind: Option<String>,
+    pub class: Option<String>,
+    pub components: Vec<Component>,
+    pub element: Element,
+}
+
+impl Analyzer {
+    fn from_element(element: &Element) -> Analyzer {
+        Analyzer {
+            kind: element.attribute("type").map(str::to_string),
+            class: element.attribute("class").map(str::to_string),
+            components: element
+                .elements()
+                .filter(|child| ["charFilter", "tokenizer", "filter"].contains(&child.local_name()))
+                .map(|child| Component {
+                    kind: child.local_name().to_string(),
+                    class: child
+                        .attribute("class")
+                        .or_else(|| child.attribute("name"))
+                        .unwrap_or_default()
+                        .to_string(),
+                    element: child.clone(),
+                })
+                .collect(),
+            element: element.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldType {
+    pub name: String,
+    pub class: String,
+    pub analyzers: Vec<Analyzer>,
+    pub element: Element,
+}
+
+impl FieldType {
+    /// The class without its `solr.` or `org.apache.solr.schema.` prefix.
+    pub fn class_name(&self) -> &str {
+        self.class.rsplit('.').next().unwrap_or(&self.class)
+    }
+
+    /// The analyzer Solr uses for `index` or `query`, falling back to the one without a type.
+    pub fn analyzer(&self, kind: &str) -> Option<&Analyzer> {
+        self.analyzers
+            .iter()
+            .find(|analyzer| analyzer.kind.as_deref() == Some(kind))
+            .or_else(|| {
+                self.analyzers
+                    .iter()
+                    .find(|analyzer| analyzer.kind.is_none() && kind != "multiterm")
+            })
+    }
+}
+
+/// Whether a field name matches a dynamicField or copyField pattern such as `*_t`, `attr_*` or `*`.
+pub fn matches_pattern(pattern: &str, name: &str) -> bool {
+    if pattern == "*" {
+        return true;
+    }
+    match (pattern.strip_prefix('*'), pattern.strip_suffix('*')) {
+        (Some(suffix), _) => name.ends_with(suffix),
+        (_, Some(prefix)) => name.starts_with(prefix),
+        _ => pattern == name,
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Field {
+    pub name: String,
+    pub type_name: String,
+    pub dynamic: bool,
+    pub element: Element,
+}
+
+impl Field {
+    pub fn matches(&self, name: &str) -> bool {
+        if self.dynamic {
+            matches_pattern(&self.name, name)
+        } else {
+            self.name == name
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CopyField {
+    pub source: String,
+    pub dest: String,
+    pub element: Element,
+}
+
+/// The definitions of a schema, resolved the same way Solr looks them up.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Schema {
+    pub version: Option<String>,
+    pub unique_key: Option<String>,
+    pub fields: Vec<Field>,
+    pub dynamic_fields: Vec<Field>,
+    pub field_types: Vec<FieldType>,
+    pub copy_fields: Vec<CopyField>,
+}
+
+impl Schema {
+    pub fn from_document(document: &Document) -> Schema {
+        let root = &document.root;
+        let mut schema = Schema {
+            version: root.attribute("version").map(str::to_string),
+            unique_key: None,
+            fields: Vec::new(),
+            dynamic_fields: Vec::new(),
+            field_types: Vec::new(),
+            copy_fields: Vec::new(),
+        };
+        for element in root.elements() {
+            let name = element.attribute("name").unwrap_or_default().to_string();
+            match element.local_name() {
+                "uniqueKey" => schema.unique_key = Some(element.text()),
+                "field" | "dynamicField" => {
+                    let field = Field {
+                        name,
+                        type_name: element.attribute("type").unwrap_or_default().to_string(),
+                        dynamic: element.local_name() == "dynamicField",
+                        element: element.clone(),
+                    };
+                    if field.dynamic {
+                        schema.dynamic_fields.push(field);
+                    } else {
+                        schema.fields.push(field);
+                    }
+                }
+                "fieldType" => schema.field_types.push(FieldType {
+                    name,
+                    class: element.attribute("class").unwrap_or_default().to_string(),
+                    analyzers: element
+                        .elements()
+                        .filter(|child| child.local_name() == "analyzer")
+                        .map(Analyzer::from_element)
+                        .collect(),
+                    element: element.clone(),
+                }),
+                "copyField" => schema.copy_fields.push(CopyField {
+                    source: element.attribute("source").unwrap_or_default().to_string(),
+                    dest: element.attribute("dest").unwrap_or_default().to_string(),
+                    element: element.clone(),
+                }),
+                _ => {}
+            }
+        }
+        schema
+    }
+
+    pub fn field_type(&self, name: &str) -> Option<&FieldType> {
+        self.field_types
+            .iter()
+            .find(|field_type| field_type.name == name)
+    }
+
+    /// Finds the field with the name, or else the dynamicField whose pattern wins for it:
+    /// the longest matching pattern, and the first declared one among equally long patterns.
+    pub fn resolve_field(&self, name: &str) -> Option<&Field> {
+        if let Some(field) = self.fields.iter().find(|field| field.name == name) {
+            return Some(field);
+        }
+        let mut matching: Vec<&Field> = self
+            .dynamic_fields
+            .iter()
+            .filter(|field| field.matches(name))
+            .collect();
+        matching.sort_by_key(|field| std::cmp::Reverse(field.name.len()));
+        matching.first().copied()
+    }
+
+    pub fn type_of(&self, field: &Field) -> Option<&FieldType> {
+        self.field_type(&field.type_name)
+    }
+
+    /// The value of a boolean property as Solr sees it: set on the field, else on the
+    /// fieldType, else the default of the property for the fieldType class.
+    pub fn property(&self, field: &Field, property: &str) -> bool {
+        self.resolve_property(field, property).0
+    }
+
+    /// Like `property`, along with where the value comes from: `field`, `fieldType` or `default`.
+    pub fn resolve_property(&self, field: &Field, property: &str) -> (bool, &'static str) {
+        let field_type = self.type_of(field);
+        if let Some(value) = field.element.attribute(property) {
+            return (value == "true", "field");
+        }
+        match field_type.and_then(|field_type| field_type.element.attribute(property)) {
+            Some(value) => (value == "true", "fieldType"),
+            None => (self.default_property(field_type, property), "default"),
+        }
+    }
+
+    /// The value of a boolean property for the fields of a fieldType that don't set it: set on
+    /// the fieldType, else the default of the property for its class.
+    pub fn type_property(&self, field_type: &FieldType, property: &str) -> bool {
+        match field_type.element.attribute(property) {
+            Some(value) => value == "true",
+            None => self.default_property(Some(field_type), property),
+        }
+    }
+
+    /// The copyFields whose source matches the field name, with the destination they write to.
+    pub fn copy_fields_from(&self, name: &str) -> Vec<(&CopyField, String)> {
+        self.copy_fields
+            .iter()
+            .filter(|copy_field| matches_pattern(&copy_field.source, name))
+            .map(|copy_field| (copy_field, copy_destination(copy_field, name)))
+            .collect()
+    }
+
+    /// The copyFields that write into the field.
+    pub fn copy_fields_into(&self, name: &str) -> Vec<&CopyField> {
+        self.copy_fields
+            .iter()
+            .filter(|copy_field| matches_pattern(&copy_field.dest, name))
+            .collect()
+    }
+
+    fn default_property(&self, field_type: Option<&FieldType>, property: &str) -> bool {
+        let class_name = field_type.map(FieldType::class_name).unwrap_or_default();
+        let version: f32 = self
+            .version
+            .as_deref()
+            .and_then(|version| version.parse().ok())
+            .unwrap_or(1.6);
+        match property {
+            "indexed" | "stored" | "uninvertible" => true,
+            "useDocValuesAsStored" => version >= 1.6,
+            "omitNorms" => class_name != "TextField" && class_name != "SortableTextField",
+            // docValues are enabled by default for the supporting classes since schema version 1.7
+            "docValues" => version >= 1.7 && DOC_VALUES_CLASSES.contains(&class_name),
+            _ => false,
+        }
+    }
+}
+
+// a glob destination such as `*_s` takes the part of the name the source glob matched
+fn copy_destination(copy_field: &CopyField, name: &str) -> String {
+    let source = copy_field.source.as_str();
+    if !copy_field.dest.contains('*') || !source.contains('*') {
+        return copy_field.dest.clone();
+    }
+    let matched = match (source.strip_prefix('*'), source.strip_suffix('*')) {
+        (Some(suffix), _) => &name[..name.len() - suffix.len()],
+        (_, Some(prefix)) => &name[prefix.len()..],
+        _ => name,
+    };
+    copy_field.dest.replacen('*', matched, 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::model::Schema;
+    use crate::tree::parse_document;
+    use std::io::Cursor;
+
+    fn schema(example: &str) -> Schema {
+        Schema::from_document(&parse_document(Cursor::new(example)).unwrap())
+    }
+
+    #[test]
+    fn test_resolve_field_prefers_fields_and_longest_pattern() {
+        let schema = schema(
+            r#"<schema version="1.6">
+            <field name="title_t" type="string" />
+            <dynamicField name="*_t" type="text" />
+            <dynamicField name="*_en_t" type="text_en" />
+            <dynamicField name="attr_*" type="string" />
+            </schema>"#,
+        );
+        assert_eq!(schema.resolve_field("title_t").unwrap().type_name, "string");
+        assert_eq!(schema.resolve_field("body_t").unwrap().name, "*_t");
+        assert_eq!(schema.resolve_field("body_en_t").unwrap().name, "*_en_t");
+        assert_eq!(schema.resolve_field("attr_color").unwrap().name, "attr_*");
+        assert_eq!(schema.resolve_field("_t").unwrap().name, "*_t");
+        assert!(schema.resolve_field("body").is_none());
+    }
+
+    #[test]
+    fn test_effective_properties() {
+        let schema = schema(
+            r#"<schema version="1.7">
+            <field name="id" type="string" stored="false" />
+            <field name="tags" type="strings" />
+            <field name="body" type="text" />
+            <fieldType name="string" class="solr.StrField" />
+            <fieldType name="strings" class="solr.StrField" multiValued="true" docValues="false" />
+            <fieldType name="text" class="solr.TextField" />
+            </schema>"#,
+        );
+        let id = schema.resolve_field("id").unwrap();
+        let tags = schema.resolve_field("tags").unwrap();
+        let body = schema.resolve_field("body").unwrap();
+        assert!(!schema.property(id, "stored"));
+        assert!(schema.property(id, "docValues"));
+        assert!(schema.property(tags, "multiValued"));
+        assert!(!schema.property(tags, "docValues"));
+        assert!(!schema.property(body, "docValues"));
+        assert!(!schema.property(body, "omitNorms"));
+    }
+}
@@ -0,0 +1,321 @@
+use std::io::{self, Seek, Write};
+
+use serde_json::{Map, Value};
+use sha2::{Digest, Sha256};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, DateTime, ZipWriter};
+
+use crate::configset::MANAGED_SCHEMA_FILES;
+use crate::resources::Resources;
+use crate::rules::{Finding, Severity};
+use crate::tree::{parse_document, Element, Node};
+
+/// The file the package describes its content in, next to solrconfig.xml.
+pub const MANIFEST_FILE: &str = "configset-manifest.json";
+
+// the files Solr reads from a configset without the schema or solrconfig.xml naming them
+const KNOWN_FILES: [&str; 8] = [
+    "solrconfig.xml",
+    "params.json",
+    "configoverlay.json",
+    "elevate.xml",
+    "stopwords.txt",
+    "synonyms.txt",
+    "protwords.txt",
+    "currency.xml",
+];
+
+// the directories of known files, e.g. the stopwords of the languages
+const KNOWN_DIRECTORIES: [&str; 1] = ["lang/"];
+
+// the managed resources the REST API stores next to the schema
+const MANAGED_RESOURCE_PREFIX: &str = "_schema_analysis_";
+
+// code and archives, which the Configsets API refuses for untrusted configsets
+const FORBIDDEN_SUFFIXES: [&str; 6] = [".jar", ".class", ".java", ".tgz", ".tar", ".gz"];
+
+fn finding(severity: Severity, message: String) -> Finding {
+    Finding {
+        rule: "package",
+        severity,
+        message,
+        fixes: Vec::new(),
+    }
+}
+
+fn fingerprint(content: &[u8]) -> String {
+    format!("sha256:{:x}", Sha256::digest(content))
+}
+
+// the values of the attributes and the texts of an element and its descendants
+fn values(element: &Element, out: &mut Vec<String>) {
+    out.extend(element.attributes.iter().map(|attr| attr.value.clone()));
+    for node in &element.children {
+        match node {
+            Node::Element(child) => values(child, out),
+            Node::Text(text) => out.push(text.clone()),
+            _ => {}
+        }
+    }
+}
+
+// the configset files an XML file of the configset names, e.g. in synonyms="synonyms.txt" or
+// <str name="config-file">elevate.xml</str>
+fn referenced_files(resources: &Resources, file: &str, names: &[String]) -> Vec<String> {
+    let Some(document) = resources
+        .read(file)
+        .and_then(|content| parse_document(content.as_bytes()).ok())
+    else {
+        return Vec::new();
+    };
+    let mut found = Vec::new();
+    values(&document.root, &mut found);
+    found
+        .iter()
+        .flat_map(|value| value.split(','))
+        .map(|value| value.trim().trim_start_matches("./"))
+        .filter(|value| names.iter().any(|name| name == value))
+        .map(str::to_string)
+        .collect()
+}
+
+/// The files of the configset that go into the package: solrconfig.xml, the schema file Solr
+/// loads, the files either of them names, the `lang` directory and the other files Solr knows.
+/// The findings are an error for code and archives, and a warning for every other file that is
+/// left out; hidden files and directories are left out silently.
+pub fn packaged_files(
+    resources: &Resources,
+    schema_file: &str,
+) -> io::Result<(Vec<String>, Vec<Finding>)> {
+    let names: Vec<String> = resources
+        .list()?
+        .into_iter()
+        .filter(|name| !name.split('/').any(|part| part.starts_with('.')))
+        .filter(|name| name != MANIFEST_FILE)
+        .collect();
+    // the files named by XML files of the package, which may name further files themselves
+    let mut referenced: Vec<String> = vec!["solrconfig.xml".to_string(), schema_file.to_string()];
+    let mut i = 0;
+    while i < referenced.len() {
+        if referenced[i].ends_with(".xml") {
+            for file in referenced_files(resources, &referenced[i], &names) {
+                if !referenced.contains(&file) {
+                    referenced.push(file);
+                }
+            }
+        }
+        i += 1;
+    }
+
+    let mut files = Vec::new();
+    let mut findings = Vec::new();
+    for name in names {
+        let file_name = name.rsplit('/').next().unwrap_or(&name);
+        if FORBIDDEN_SUFFIXES
+            .iter()
+            .any(|suffix| name.to_lowercase().ends_with(suffix))
+        {
+            findings.push(finding(
+                Severity::Error,
+                format!(
+                    "The file '{}' is code or an archive, which a configset must not contain.",
+                    name
+                ),
+            ));
+        } else if referenced.contains(&name)
+            || KNOWN_FILES.contains(&name.as_str())
+            || KNOWN_DIRECTORIES
+                .iter()
+                .any(|directory| name.starts_with(directory))
+            || file_name.starts_with(MANAGED_RESOURCE_PREFIX)
+        {
+            files.push(name);
+        } else if !MANAGED_SCHEMA_FILES.contains(&name.as_str()) {
+            findings.push(finding(
+                Severity::Warning,
+                format!(
+                    "The file '{}' is not a file Solr reads and is left out of the package.",
+                    name
+                ),
+            ));
+        }
+    }
+    Ok((files, findings))
+}
+
+/// Reads the files of the package, failing on the first one that can't be read.
+pub fn read_files(resources: &Resources, files: &[String]) -> io::Result<Vec<(String, Vec<u8>)>> {
+    files
+        .iter()
+        .map(|name| {
+            let content = resources.read_bytes(name).ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::NotFound,
+                    format!("Could not read '{}'.", name),
+                )
+            })?;
+            Ok((name.clone(), content))
+        })
+        .collect()
+}
+
+/// The manifest of the package: the schema file with its fingerprint, and the fingerprint of
+/// every file.
+pub fn manifest(files: &[(String, Vec<u8>)], schema_file: &str) -> io::Result<String> {
+    let mut fingerprints = Map::new();
+    for (name, content) in files {
+        fingerprints.insert(name.clone(), Value::from(fingerprint(content)));
+    }
+    let schema = files
+        .iter()
+        .find(|(name, _)| name == schema_file)
+        .ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("The package has no '{}'.", schema_file),
+            )
+        })?;
+    let manifest = serde_json::json!({
+        "schema": schema_file,
+        "schema-fingerprint": fingerprint(&schema.1),
+        "files": fingerprints,
+    });
+    let mut output = serde_json::to_string_pretty(&manifest).map_err(io::Error::other)?;
+    output.push('\n');
+    Ok(output)
+}
+
+/// Writes the files and the manifest as a zip for the Configsets API: the paths are relative to
+/// the configset with forward slashes, in order and with a fixed time, so that the same
+/// configset always gives the same zip.
+pub fn write_package(
+    files: &[(String, Vec<u8>)],
+    schema_file: &str,
+    writer: impl Write + Seek,
+) -> io::Result<()> {
+    let manifest = manifest(files, schema_file)?;
+    let options = SimpleFileOptions::default()
+        .compression_method(CompressionMethod::Deflated)
+        .last_modified_time(DateTime::default())
+        .unix_permissions(0o644);
+    let mut zip = ZipWriter::new(writer);
+    for (name, content) in files {
+        zip.start_file(name.as_str(), options)?;
+        zip.write_all(content)?;
+    }
+    zip.start_file(MANIFEST_FILE, options)?;
+    zip.write_all(manifest.as_bytes())?;
+    zip.finish()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::package::{packaged_files, read_files, write_package, MANIFEST_FILE};
+    use crate::resources::Resources;
+    use std::io::{Cursor, Write};
+    use zip::write::SimpleFileOptions;
+    use zip::ZipWriter;
+
+    #[test]
+    fn test_package() {
+        let resources = Resources::default()
+            .with_file(
+                "solrconfig.xml",
+                r#"<config><searchComponent name="elevator" class="solr.QueryElevationComponent"><str name="config-file">elevate-books.xml</str></searchComponent></config>"#,
+            )
+            .with_file(
+                "managed-schema.xml",
+                r#"<schema><fieldType name="text" class="solr.TextField"><analyzer><filter class="solr.SynonymGraphFilterFactory" synonyms="synonyms_books.txt" /></analyzer></fieldType></schema>"#,
+            )
+            .with_file("schema.xml", "<schema />")
+            .with_file("elevate-books.xml", "<elevate />")
+            .with_file("synonyms_books.txt", "tv, television\n")
+            .with_file("lang/stopwords_en.txt", "a\n")
+            .with_file("lang/.stopwords_en.txt.swp", "")
+            .with_file("synonyms.txt~", "")
+            .with_file("NOTES.md", "")
+            .with_file(".git/config", "");
+        let (files, findings) = packaged_files(&resources, "managed-schema.xml").unwrap();
+        assert_eq!(
+            files,
+            vec![
+                "elevate-books.xml",
+                "lang/stopwords_en.txt",
+                "managed-schema.xml",
+                "solrconfig.xml",
+                "synonyms_books.txt"
+            ]
+        );
+        let findings: Vec<String> = findings.iter().map(|finding| finding.to_string()).collect();
+        assert_eq!(
+            findings,
+            vec![
+                "warning[package]: The file 'NOTES.md' is not a file Solr reads and is left out of the package.",
+                "warning[package]: The file 'synonyms.txt~' is not a file Solr reads and is left out of the package.",
+            ]
+        );
+
+        let mut zip = Cursor::new(Vec::new());
+        let contents = read_files(&resources, &files).unwrap();
+        write_package(&contents, "managed-schema.xml", &mut zip).unwrap();
+        zip.set_position(0);
+        let packaged = Resources::from_zip(zip).unwrap();
+        let mut expected = files.clone();
+        expected.insert(0, MANIFEST_FILE.to_string());
+        assert_eq!(packaged.list().unwrap(), expected);
+        let manifest: serde_json::Value =
+            serde_json::from_str(&packaged.read(MANIFEST_FILE).unwrap()).unwrap();
+        assert_eq!(manifest["schema"], "managed-schema.xml");
+        assert_eq!(
+            manifest["files"]["managed-schema.xml"],
+            manifest["schema-fingerprint"]
+        );
+        // the SHA-256 of "<elevate />"
+        assert_eq!(
+            manifest["files"]["elevate-books.xml"],
+            "sha256:06a6cc2893b7bc687cff87555425952cdd702e77ea6f5a516eed9ed149453617"
+        );
+    }
+
+    #[test]
+    fn test_package_refuses_code_and_archives() {
+        let resources = Resources::default()
+            .with_file("solrconfig.xml", "<config />")
+            .with_file("schema.xml", "<schema />")
+            .with_file("lib/plugin.jar", "")
+            .with_file("backup.tar.gz", "");
+        let (files, findings) = packaged_files(&resources, "schema.xml").unwrap();
+        assert_eq!(files, vec!["schema.xml", "solrconfig.xml"]);
+        let findings: Vec<String> = findings.iter().map(|finding| finding.to_string()).collect();
+        assert_eq!(
+            findings,
+            vec![
+                "error[package]: The file 'backup.tar.gz' is code or an archive, which a configset must not contain.",
+                "error[package]: The file 'lib/plugin.jar' is code or an archive, which a configset must not contain.",
+            ]
+        );
+        assert!(read_files(&resources, &["stopwords.txt".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_package_from_zip() {
+        let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+        for (name, content) in [
+            ("books/conf/solrconfig.xml", "<config />"),
+            ("books/conf/schema.xml", "<schema />"),
+            ("books/conf/.DS_Store", ""),
+        ] {
+            zip.start_file(name, SimpleFileOptions::default()).unwrap();
+            zip.write_all(content.as_bytes()).unwrap();
+        }
+        let mut zip = zip.finish().unwrap();
+        zip.set_position(0);
+        let resources = Resources::from_zip(zip).unwrap();
+        assert_eq!(resources.read("schema.xml").unwrap(), "<schema />");
+        assert_eq!(
+            packaged_files(&resources, "schema.xml").unwrap().0,
+            vec!["schema.xml", "solrconfig.xml"]
+        );
+    }
+}
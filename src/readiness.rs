@@ -0,0 +1,210 @@
+use std::fmt;
+
+use crate::model::{matches_pattern, Field, FieldType, Schema};
+
+// the classes whose docValues can be updated in place
+const IN_PLACE_CLASSES: [&str; 4] = [
+    "IntPointField",
+    "LongPointField",
+    "FloatPointField",
+    "DoublePointField",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, clap::ValueEnum)]
+pub enum Analysis {
+    AtomicUpdates,
+}
+
+/// Which fields keep a schema from atomic updates, and which fields can be updated in place.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct AtomicUpdateReadiness {
+    pub missing_version: bool,
+    /// The fields or dynamicFields that block atomic updates, with the reason.
+    pub blocking: Vec<(String, &'static str)>,
+    /// Whether `_version_` is neither indexed nor stored and has docValues, without which no
+    /// update happens in place.
+    pub in_place_version: bool,
+    pub in_place: Vec<String>,
+}
+
+fn is_copy_destination(schema: &Schema, field: &Field) -> bool {
+    schema.copy_fields.iter().any(|copy_field| {
+        copy_field.dest == field.name
+            || (!field.dynamic && matches_pattern(&copy_field.dest, &field.name))
+    })
+}
+
+/// Atomic updates rebuild the document from its stored and docValues fields, so every field
+/// other than the copyField destinations must keep its values; the destinations are copied
+/// into again and must not be stored or their values pile up.
+pub fn atomic_update_readiness(schema: &Schema) -> AtomicUpdateReadiness {
+    let version = schema.fields.iter().find(|field| field.name == "_version_");
+    let mut readiness = AtomicUpdateReadiness {
+        missing_version: version.is_none(),
+        in_place_version: version.is_some_and(|version| {
+            schema.property(version, "docValues")
+                && !schema.property(version, "indexed")
+                && !schema.property(version, "stored")
+        }),
+        ..Default::default()
+    };
+    for field in schema.fields.iter().chain(&schema.dynamic_fields) {
+        if field.name == "_version_" {
+            continue;
+        }
+        let property = |name: &str| schema.property(field, name);
+        if is_copy_destination(schema, field) {
+            if property("stored") {
+                readiness
+                    .blocking
+                    .push((field.name.clone(), "copyField destination is stored"));
+            }
+            continue;
+        }
+        if !property("stored") && !property("docValues") {
+            readiness
+                .blocking
+                .push((field.name.clone(), "neither stored nor docValues"));
+        }
+        let class_name = schema
+            .type_of(field)
+            .map(FieldType::class_name)
+            .unwrap_or_default();
+        if readiness.in_place_version
+            && IN_PLACE_CLASSES.contains(&class_name)
+            && property("docValues")
+            && !property("multiValued")
+            && !property("indexed")
+            && !property("stored")
+        {
+            readiness.in_place.push(field.name.clone());
+        }
+    }
+    readiness
+}
+
+impl fmt::Display for AtomicUpdateReadiness {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.missing_version {
+            writeln!(
+                f,
+                "Atomic updates: blocked, the schema has no _version_ field"
+            )?;
+        }
+        if !self.blocking.is_empty() {
+            writeln!(
+                f,
+                "Atomic updates: blocked by {} fields",
+                self.blocking.len()
+            )?;
+        } else if !self.missing_version {
+            writeln!(f, "Atomic updates: ready")?;
+        }
+        for (name, reason) in &self.blocking {
+            writeln!(f, "  {}: {}", name, reason)?;
+        }
+        if !self.in_place_version {
+            return writeln!(
+                f,
+                "In-place updates: blocked, _version_ must be neither indexed nor stored and have docValues"
+            );
+        }
+        writeln!(
+            f,
+            "In-place updates: {} fields eligible",
+            self.in_place.len()
+        )?;
+        for name in &self.in_place {
+            writeln!(f, "  {}", name)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::model::Schema;
+    use crate::readiness::atomic_update_readiness;
+    use crate::tree::parse_document;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_atomic_update_readiness() {
+        let schema = Schema::from_document(
+            &parse_document(Cursor::new(
+                r#"<schema version="1.7">
+  <field name="_version_" type="plong" indexed="false" stored="false" />
+  <field name="id" type="string" />
+  <field name="title" type="string" />
+  <field name="body" type="text" stored="false" />
+  <field name="all" type="text" stored="true" multiValued="true" />
+  <field name="popularity" type="pint" indexed="false" stored="false" />
+  <field name="price" type="pfloat" stored="false" />
+  <dynamicField name="*_txt" type="text" stored="false" />
+  <dynamicField name="*_i" type="pint" indexed="false" stored="false" />
+  <copyField source="title" dest="all" />
+  <copyField source="title" dest="*_txt" />
+  <fieldType name="string" class="solr.StrField" />
+  <fieldType name="text" class="solr.TextField" />
+  <fieldType name="pint" class="solr.IntPointField" />
+  <fieldType name="pfloat" class="solr.FloatPointField" />
+  <fieldType name="plong" class="solr.LongPointField" />
+</schema>"#,
+            ))
+            .unwrap(),
+        );
+        let readiness = atomic_update_readiness(&schema);
+        assert_eq!(
+            readiness.to_string(),
+            "Atomic updates: blocked by 2 fields
+  body: neither stored nor docValues
+  all: copyField destination is stored
+In-place updates: 2 fields eligible
+  popularity
+  *_i
+"
+        );
+    }
+
+    #[test]
+    fn test_atomic_update_readiness_without_version() {
+        let schema = Schema::from_document(
+            &parse_document(Cursor::new(
+                r#"<schema version="1.7">
+  <field name="id" type="string" />
+  <field name="popularity" type="pint" indexed="false" stored="false" />
+  <fieldType name="string" class="solr.StrField" />
+  <fieldType name="pint" class="solr.IntPointField" />
+</schema>"#,
+            ))
+            .unwrap(),
+        );
+        let readiness = atomic_update_readiness(&schema);
+        assert!(readiness.in_place.is_empty());
+        assert_eq!(
+            readiness.to_string(),
+            "Atomic updates: blocked, the schema has no _version_ field
+In-place updates: blocked, _version_ must be neither indexed nor stored and have docValues
+"
+        );
+
+        // a stored _version_ allows atomic updates but no update in place
+        let schema = Schema::from_document(
+            &parse_document(Cursor::new(
+                r#"<schema version="1.7">
+  <field name="_version_" type="plong" stored="true" />
+  <field name="popularity" type="pint" indexed="false" stored="false" />
+  <fieldType name="pint" class="solr.IntPointField" />
+  <fieldType name="plong" class="solr.LongPointField" />
+</schema>"#,
+            ))
+            .unwrap(),
+        );
+        assert_eq!(
+            atomic_update_readiness(&schema).to_string(),
+            "Atomic updates: ready
+In-place updates: blocked, _version_ must be neither indexed nor stored and have docValues
+"
+        );
+    }
+}
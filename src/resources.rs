@@ -0,0 +1,160 @@
+use std::cell::RefCell;
+use std::fs;
+use std::io::{self, Read, Seek};
+use std::path::{Path, PathBuf};
+
+use zip::ZipArchive;
+
+use crate::remote::SolrClient;
+use crate::tree::parse_document;
+
+/// The files next to the schema in its configset, such as an enumsConfig or a stopwords file.
+#[derive(Debug, Clone, Default)]
+pub struct Resources {
+    directory: Option<PathBuf>,
+    files: Vec<(String, Vec<u8>)>,
+    remote: Option<SolrClient>,
+    // the files that could not be fetched from the remote for another reason than their absence
+    failures: RefCell<Vec<String>>,
+}
+
+impl Resources {
+    pub fn in_directory(directory: &Path) -> Resources {
+        Resources {
+            directory: Some(directory.to_path_buf()),
+            ..Resources::default()
+        }
+    }
+
+    /// The files of a configset zip; directories that hold all of them, such as `conf`, are left
+    /// out.
+    pub fn from_zip(reader: impl Read + Seek) -> io::Result<Resources> {
+        let mut archive = ZipArchive::new(reader)?;
+        let mut files = Vec::new();
+        for i in 0..archive.len() {
+            let mut entry = archive.by_index(i)?;
+            // directories, and names that would escape the configset
+            let Some(path) = entry.enclosed_name().filter(|_| !entry.is_dir()) else {
+                continue;
+            };
+            let name: Vec<String> = path
+                .components()
+                .map(|component| component.as_os_str().to_string_lossy().into_owned())
+                .collect();
+            let mut content = Vec::new();
+            entry.read_to_end(&mut content)?;
+            files.push((name.join("/"), content));
+        }
+        // e.g. books/conf/solrconfig.xml, as zipped from above the configset
+        while let Some(directory) = common_directory(&files) {
+            for (name, _) in &mut files {
+                name.replace_range(..=directory, "");
+            }
+        }
+        Ok(Resources {
+            files,
+            ..Resources::default()
+        })
+    }
+
+    /// The configset of a running Solr, read through its file endpoint.
+    pub fn of_remote(client: SolrClient) -> Resources {
+        Resources {
+            remote: Some(client),
+            ..Resources::default()
+        }
+    }
+
+    /// The directory of the schema file, i.e. the configset `conf` directory.
+    pub fn of_schema(file: &str) -> Resources {
+        Resources::in_directory(Path::new(file).parent().unwrap_or(Path::new("")))
+    }
+
+    #[cfg(test)]
+    pub fn with_file(mut self, name: &str, content: impl AsRef<[u8]>) -> Resources {
+        self.files.retain(|(file, _)| file != name);
+        self.files
+            .push((name.to_string(), content.as_ref().to_vec()));
+        self
+    }
+
+    /// The bytes of a file relative to the configset, or `None` if it can't be read; why a remote
+    /// file could not be fetched is kept for `failures`.
+    pub fn read_bytes(&self, name: &str) -> Option<Vec<u8>> {
+        if let Some((_, content)) = self.files.iter().find(|(file, _)| file == name) {
+            return Some(content.clone());
+        }
+        if let Some(client) = &self.remote {
+            return client.fetch_file(name).unwrap_or_else(|e| {
+                let mut failures = self.failures.borrow_mut();
+                if !failures.contains(&e.to_string()) {
+                    failures.push(e.to_string());
+                }
+                None
+            });
+        }
+        fs::read(self.directory.as_ref()?.join(name)).ok()
+    }
+
+    /// The errors of the remote files that could not be fetched though they may exist, such as
+    /// network, TLS or authorization errors.
+    pub fn failures(&self) -> Vec<String> {
+        self.failures.borrow().clone()
+    }
+
+    /// The paths of the files of the configset, relative to it with forward slashes, sorted.
+    pub fn list(&self) -> io::Result<Vec<String>> {
+        let mut names: Vec<String> = self.files.iter().map(|(name, _)| name.clone()).collect();
+        if let Some(directory) = &self.directory {
+            list_directory(directory, "", &mut names)?;
+        }
+        names.sort();
+        names.dedup();
+        Ok(names)
+    }
+
+    /// The content of a file relative to the configset, or `None` if it can't be read as UTF-8.
+    pub fn read(&self, name: &str) -> Option<String> {
+        String::from_utf8(self.read_bytes(name)?).ok()
+    }
+}
+
+// the length of the top-level directory all the files are in, if they are in a single one
+fn common_directory(files: &[(String, Vec<u8>)]) -> Option<usize> {
+    let (first, _) = files.first()?;
+    let directory = first.find('/')?;
+    files
+        .iter()
+        .all(|(name, _)| {
+            name.find('/') == Some(directory) && name[..directory] == first[..directory]
+        })
+        .then_some(directory)
+}
+
+fn list_directory(directory: &Path, prefix: &str, names: &mut Vec<String>) -> io::Result<()> {
+    for entry in fs::read_dir(directory)? {
+        let entry = entry?;
+        let name = format!("{}{}", prefix, entry.file_name().to_string_lossy());
+        if entry.file_type()?.is_dir() {
+            list_directory(&entry.path(), &format!("{}/", name), names)?;
+        } else {
+            names.push(name);
+        }
+    }
+    Ok(())
+}
+
+/// The values of an enum in an enumsConfig file, or `None` if the file doesn't define it.
+pub fn enum_values(content: &str, enum_name: &str) -> Option<Vec<String>> {
+    let document = parse_document(content.as_bytes()).ok()?;
+    let definition = document.root.elements().find(|element| {
+        element.local_name() == "enum" && element.attribute("name") == Some(enum_name)
+    })?;
+    Some(
+        definition
+            .elements()
+            .filter(|element| element.local_name() == "value")
+            .map(|element| element.text())
+            .collect(),
+    )
+}
@@ -0,0 +1,888 @@
+use std::fmt;
+
+use regex::Regex;
+
+use crate::analysis::{check_analysis_resources, check_analyzer_chains};
+use crate::classes::{check_analyzable_classes, check_class_attributes};
+use crate::config::Config;
+use crate::fix::{apply_fixes, Edit, Fix};
+use crate::model::{Field, FieldType, Schema};
+use crate::resources::{enum_values, Resources};
+use crate::schema::{DEPRECATED_FIELD_TYPES, OPTIONAL_FIELD_PROPERTIES, SOLR_CONSTANT_TYPE_NAMES};
+use crate::solrconfig::{check_request_handlers, check_update_processors, SolrConfig};
+use crate::tree::{parse_document, Document, Element};
+use crate::values::check_value;
+
+const SCHEMA_CLASS_PREFIX: &str = "org.apache.solr.schema.";
+
+const SHORT_CLASS_PREFIX: &str = "solr.";
+
+// fieldType attributes that refer to another fieldType by its name
+const FIELD_TYPE_REFERENCES: [&str; 2] = ["numberType", "subFieldType"];
+
+// deprecated classes with exactly one replacement; the Point types also need docValues
+const DEPRECATED_FIELD_TYPE_REPLACEMENTS: [(&str, &str); 5] = [
+    ("TrieIntField", "IntPointField"),
+    ("TrieLongField", "LongPointField"),
+    ("TrieFloatField", "FloatPointField"),
+    ("TrieDoubleField", "DoublePointField"),
+    ("TrieDateField", "DatePointField"),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Warning,
+    Error,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Severity::Warning => write!(f, "warning"),
+            Severity::Error => write!(f, "error"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Finding {
+    pub rule: &'static str,
+    pub severity: Severity,
+    pub message: String,
+    pub fixes: Vec<Fix>,
+}
+
+impl fmt::Display for Finding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}[{}]: {}", self.severity, self.rule, self.message)
+    }
+}
+
+pub fn check_rules(document: &Document, config: &Config, resources: &Resources) -> Vec<Finding> {
+    let schema = Schema::from_document(document);
+    let mut findings = Vec::new();
+    findings.extend(check_deprecated_field_types(document));
+    findings.extend(check_boolean_case(document));
+    findings.extend(check_class_prefixes(document));
+    findings.extend(check_unused_field_types(&schema));
+    findings.extend(check_unreachable_analyzers(&schema));
+    findings.extend(check_naming(&schema, config));
+    findings.extend(check_default_values(&schema, resources));
+    findings.extend(check_system_fields(&schema, resources));
+    findings.extend(check_class_attributes(&schema, config, resources));
+    findings.extend(check_analyzable_classes(&schema));
+    findings.extend(check_analysis_resources(&schema, resources));
+    findings.extend(check_analyzer_chains(&schema));
+    findings.extend(check_request_handlers(&schema, resources));
+    findings.extend(check_update_processors(&schema, config, resources));
+    findings
+}
+
+/// Applies the fixes of all findings until none is left, since two fixes of one attribute
+/// (e.g. a deprecated class with the long prefix) can only be applied one after the other.
+pub fn fix_schema(
+    content: &str,
+    config: &Config,
+    resources: &Resources,
+) -> Result<(String, usize), xml::reader::Error> {
+    const MAX_PASSES: usize = 5;
+    let mut fixed = content.to_string();
+    let mut applied = 0;
+    for _ in 0..MAX_PASSES {
+        let document = parse_document(fixed.as_bytes())?;
+        let fixes: Vec<Fix> = check_rules(&document, config, resources)
+            .into_iter()
+            .flat_map(|finding| finding.fixes)
+            .collect();
+        let (next, count) = apply_fixes(&fixed, &fixes);
+        if next == fixed {
+            break;
+        }
+        applied += count;
+        fixed = next;
+    }
+    Ok((fixed, applied))
+}
+
+fn set_attribute(element: &Element, name: &str, value: &str) -> Fix {
+    Fix {
+        start: element.start,
+        end: element.end,
+        edit: Edit::SetAttribute {
+            name: name.to_string(),
+            value: value.to_string(),
+        },
+    }
+}
+
+pub(crate) fn remove_attribute(element: &Element, name: &str) -> Fix {
+    Fix {
+        start: element.start,
+        end: element.end,
+        edit: Edit::RemoveAttribute {
+            name: name.to_string(),
+        },
+    }
+}
+
+fn remove_element(element: &Element) -> Fix {
+    Fix {
+        start: element.start,
+        end: element.end,
+        edit: Edit::RemoveElement,
+    }
+}
+
+fn field_types(document: &Document) -> impl Iterator<Item = &Element> {
+    document
+        .root
+        .elements()
+        .filter(|element| element.local_name() == "fieldType")
+}
+
+fn check_deprecated_field_types(document: &Document) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    for field_type in field_types(document) {
+        let Some(class) = field_type.attribute("class") else {
+            continue;
+        };
+        let class_name = class.rsplit('.').next().unwrap_or(class);
+        let prefix = &class[..class.len() - class_name.len()];
+        if !DEPRECATED_FIELD_TYPES.contains(&class_name) {
+            continue;
+        }
+        let name = field_type.attribute("name").unwrap_or_default();
+        let replacement = DEPRECATED_FIELD_TYPE_REPLACEMENTS
+            .iter()
+            .find(|(deprecated, _)| *deprecated == class_name);
+        let finding = match replacement {
+            Some((_, replacement)) => {
+                let mut fixes = vec![set_attribute(
+                    field_type,
+                    "class",
+                    &format!("{}{}", prefix, replacement),
+                )];
+                let mut message = format!(
+                    "The fieldType '{}' uses the deprecated class '{}', use '{}' with docValues instead.",
+                    name, class, replacement
+                );
+                // an explicit docValues="false" is a choice about the index, which the fix keeps
+                match field_type.attribute("docValues") {
+                    None => fixes.push(set_attribute(field_type, "docValues", "true")),
+                    Some("true") => {}
+                    Some(value) => message.push_str(&format!(
+                        " It sets docValues=\"{}\", so the {} can't be sorted, faceted or used in function queries efficiently.",
+                        value, replacement
+                    )),
+                }
+                Finding {
+                    rule: "deprecated-field-type",
+                    severity: Severity::Error,
+                    message,
+                    fixes,
+                }
+            }
+            None => Finding {
+                rule: "deprecated-field-type",
+                severity: Severity::Error,
+                message: format!(
+                    "The fieldType '{}' uses the deprecated class '{}'.",
+                    name, class
+                ),
+                fixes: Vec::new(),
+            },
+        };
+        findings.push(finding);
+    }
+    findings
+}
+
+fn check_boolean_case(document: &Document) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    let elements = document
+        .root
+        .elements()
+        .filter(|element| ["field", "dynamicField", "fieldType"].contains(&element.local_name()));
+    for element in elements {
+        for attribute in &element.attributes {
+            let property = attribute.name.local_name.as_str();
+            if property == "default" || !OPTIONAL_FIELD_PROPERTIES.contains(&property) {
+                continue;
+            }
+            let lowercase = attribute.value.to_ascii_lowercase();
+            if attribute.value != lowercase && (lowercase == "true" || lowercase == "false") {
+                findings.push(Finding {
+                    rule: "boolean-case",
+                    severity: Severity::Error,
+                    message: format!(
+                        "The value '{}' of {} in {}={} must be lowercase.",
+                        attribute.value,
+                        property,
+                        element.local_name(),
+                        element.attribute("name").unwrap_or_default()
+                    ),
+                    fixes: vec![set_attribute(element, property, &lowercase)],
+                });
+            }
+        }
+    }
+    findings
+}
+
+fn check_class_prefixes(document: &Document) -> Vec<Finding> {
+    let classes: Vec<(&Element, &str)> = field_types(document)
+        .filter_map(|field_type| Some((field_type, field_type.attribute("class")?)))
+        .collect();
+    let mixed = classes
+        .iter()
+        .any(|(_, class)| class.starts_with(SHORT_CLASS_PREFIX));
+    if !mixed {
+        return Vec::new();
+    }
+    classes
+        .iter()
+        .filter_map(|(field_type, class)| {
+            let class_name = class.strip_prefix(SCHEMA_CLASS_PREFIX)?;
+            let short = format!("{}{}", SHORT_CLASS_PREFIX, class_name);
+            Some(Finding {
+                rule: "class-prefix",
+                severity: Severity::Warning,
+                message: format!(
+                    "The fieldType '{}' uses '{}' while other types use the '{}' prefix, use '{}'.",
+                    field_type.attribute("name").unwrap_or_default(),
+                    class,
+                    SHORT_CLASS_PREFIX,
+                    short
+                ),
+                fixes: vec![set_attribute(field_type, "class", &short)],
+            })
+        })
+        .collect()
+}
+
+fn check_unused_field_types(schema: &Schema) -> Vec<Finding> {
+    let mut used: Vec<&str> = schema
+        .fields
+        .iter()
+        .chain(&schema.dynamic_fields)
+        .map(|field| field.type_name.as_str())
+        .collect();
+    for field_type in &schema.field_types {
+        used.extend(
+            FIELD_TYPE_REFERENCES
+                .iter()
+                .filter_map(|reference| field_type.element.attribute(reference)),
+        );
+    }
+    schema
+        .field_types
+        .iter()
+        .filter(|field_type| !used.contains(&field_type.name.as_str()))
+        .map(|field_type| Finding {
+            rule: "unused-field-type",
+            severity: Severity::Warning,
+            message: format!(
+                "The fieldType '{}' is not used by any field or dynamicField.",
+                field_type.name
+            ),
+            // schemaless update chains add fields of the types that no field uses yet
+            fixes: Vec::new(),
+        })
+        .collect()
+}
+
+fn check_unreachable_analyzers(schema: &Schema) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    for field_type in &schema.field_types {
+        let typed = |kind: &str| {
+            field_type
+                .analyzers
+                .iter()
+                .any(|analyzer| analyzer.kind.as_deref() == Some(kind))
+        };
+        for (i, analyzer) in field_type.analyzers.iter().enumerate() {
+            let shadowed = field_type.analyzers[..i]
+                .iter()
+                .any(|previous| previous.kind == analyzer.kind);
+            // an analyzer without a type is only a fallback for the index and query analyzers
+            let untyped_unused = analyzer.kind.is_none() && typed("index") && typed("query");
+            if shadowed || untyped_unused {
+                findings.push(Finding {
+                    rule: "unreachable-analyzer",
+                    severity: Severity::Warning,
+                    message: format!(
+                        "The {} analyzer of the fieldType '{}' is never used.",
+                        analyzer.kind.as_deref().unwrap_or("untyped"),
+                        field_type.name
+                    ),
+                    fixes: vec![remove_element(&analyzer.element)],
+                });
+            }
+        }
+    }
+    findings
+}
+
+fn check_naming(schema: &Schema, config: &Config) -> Vec<Finding> {
+    let pattern =
+        |pattern: &str| Regex::new(pattern).expect("patterns are validated with the config");
+    let naming = [
+        ("field", pattern(&config.naming.field)),
+        ("dynamicField", pattern(&config.naming.dynamic_field)),
+        ("fieldType", pattern(&config.naming.field_type)),
+    ];
+    let names = schema
+        .fields
+        .iter()
+        .map(|field| ("field", field.name.as_str()))
+        .chain(
+            schema
+                .dynamic_fields
+                .iter()
+                .map(|field| ("dynamicField", field.name.as_str())),
+        )
+        .chain(
+            schema
+                .field_types
+                .iter()
+                .map(|field_type| ("fieldType", field_type.name.as_str())),
+        );
+
+    let mut findings = Vec::new();
+    for (kind, name) in names {
+        if SOLR_CONSTANT_TYPE_NAMES.contains(&name) {
+            continue;
+        }
+        if kind == "field" && name.len() > 1 && name.starts_with('_') && name.ends_with('_') {
+            findings.push(Finding {
+                rule: "reserved-field-name",
+                severity: Severity::Warning,
+                message: format!(
+                    "The field '{}' has leading and trailing underscores, which are reserved for Solr's own fields such as {:?}.",
+                    name, SOLR_CONSTANT_TYPE_NAMES
+                ),
+                fixes: Vec::new(),
+            });
+            continue;
+        }
+        let (_, regex) = naming.iter().find(|(k, _)| *k == kind).unwrap();
+        let checked = if kind == "dynamicField" {
+            name.trim_matches('*')
+        } else {
+            name
+        };
+        // the catch-all dynamicField `*` has no name of its own to check
+        if checked.is_empty() || regex.is_match(checked) {
+            continue;
+        }
+        let mut message = format!(
+            "The {} name '{}' does not match the pattern '{}'.",
+            kind,
+            name,
+            regex.as_str()
+        );
+        if checked.contains(['-', '.', ' ']) {
+            message.push_str(" Hyphens, dots and spaces break function queries.");
+        }
+        findings.push(Finding {
+            rule: "naming",
+            severity: Severity::Warning,
+            message,
+            fixes: Vec::new(),
+        });
+    }
+    findings
+}
+
+fn check_default_values(schema: &Schema, resources: &Resources) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    for field in schema.fields.iter().chain(&schema.dynamic_fields) {
+        let Some(default) = field.element.attribute("default") else {
+            continue;
+        };
+        if field.element.attribute("required") == Some("true") {
+            findings.push(Finding {
+                rule: "required-with-default",
+                severity: Severity::Warning,
+                message: format!(
+                    "The field '{}' is required and has a default, so it can never be missing and the requirement is never checked.",
+                    field.name
+                ),
+                fixes: Vec::new(),
+            });
+        }
+        let Some(field_type) = schema.type_of(field) else {
+            continue;
+        };
+        let valid = match field_type.class_name() {
+            "EnumField" | "EnumFieldType" => check_enum_value(
+                field_type.element.attribute("enumsConfig"),
+                field_type.element.attribute("enumName"),
+                default,
+                resources,
+            ),
+            class_name => check_value(class_name, default),
+        };
+        if let Err(e) = valid {
+            findings.push(Finding {
+                rule: "invalid-default",
+                severity: Severity::Error,
+                message: format!(
+                    "The default of the field '{}' is invalid: {}.",
+                    field.name, e
+                ),
+                fixes: Vec::new(),
+            });
+        }
+    }
+    findings
+}
+
+fn check_system_fields(schema: &Schema, resources: &Resources) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    let mut report = |severity: Severity, message: String| {
+        findings.push(Finding {
+            rule: "system-field",
+            severity,
+            message,
+            fixes: Vec::new(),
+        })
+    };
+    let field = |name: &str| schema.fields.iter().find(|field| field.name == name);
+    let class_name = |field: &Field| {
+        schema
+            .type_of(field)
+            .map(FieldType::class_name)
+            .unwrap_or_default()
+            .to_string()
+    };
+
+    match field("_version_") {
+        Some(version) => {
+            if !["LongPointField", "TrieLongField"].contains(&class_name(version).as_str()) {
+                report(
+                    Severity::Error,
+                    format!(
+                        "The field '_version_' must be a long, but its fieldType '{}' is a {}.",
+                        version.type_name,
+                        class_name(version)
+                    ),
+                );
+            }
+            if schema.property(version, "multiValued") {
+                report(
+                    Severity::Error,
+                    "The field '_version_' must not be multiValued.".to_string(),
+                );
+            }
+            if !schema.property(version, "docValues") {
+                report(
+                    Severity::Error,
+                    "The field '_version_' needs docValues.".to_string(),
+                );
+            }
+        }
+        None => match SolrConfig::load(resources) {
+            // Solr doesn't load a core whose updateLog has no _version_ field
+            Some(solrconfig) if solrconfig.update_log => report(
+                Severity::Error,
+                "The solrconfig.xml enables the updateLog, but the schema has no '_version_' field, which it needs."
+                    .to_string(),
+            ),
+            Some(_) => {}
+            // without solrconfig.xml, a uniqueKey hints at the updateLog that atomic updates and SolrCloud need
+            None if schema.unique_key.is_some() => report(
+                Severity::Warning,
+                "The schema has a uniqueKey but no '_version_' field, which the updateLog needs."
+                    .to_string(),
+            ),
+            None => {}
+        },
+    }
+
+    let nest_path = field("_nest_path_");
+    if let Some(nest_path) = nest_path {
+        if class_name(nest_path) != "NestPathField" {
+            report(
+                Severity::Error,
+                format!(
+                    "The field '_nest_path_' must use a NestPathField, but its fieldType '{}' is a {}.",
+                    nest_path.type_name,
+                    class_name(nest_path)
+                ),
+            );
+        }
+    }
+    match field("_root_") {
+        Some(root) => {
+            if class_name(root) != "StrField" {
+                report(
+                    Severity::Error,
+                    format!(
+                        "The field '_root_' must be a string, but its fieldType '{}' is a {}.",
+                        root.type_name,
+                        class_name(root)
+                    ),
+                );
+            }
+            if !schema.property(root, "indexed") {
+                report(
+                    Severity::Error,
+                    "The field '_root_' must be indexed to find the children of a document."
+                        .to_string(),
+                );
+            }
+        }
+        None if nest_path.is_some() => report(
+            Severity::Error,
+            "The schema has '_nest_path_' but no '_root_' field, which nested documents need."
+                .to_string(),
+        ),
+        None => {}
+    }
+
+    if let Some(text) = field("_text_") {
+        if !schema.property(text, "multiValued") {
+            report(
+                Severity::Warning,
+                "The field '_text_' is not multiValued, so copying more than one field into it fails."
+                    .to_string(),
+            );
+        }
+        if schema.copy_fields_into("_text_").is_empty() {
+            report(
+                Severity::Warning,
+                "No copyField writes into the field '_text_'.".to_string(),
+            );
+        }
+    }
+    findings
+}
+
+// an enum value can only be checked when the enumsConfig file is found and defines the enum
+fn check_enum_value(
+    enums_config: Option<&str>,
+    enum_name: Option<&str>,
+    value: &str,
+    resources: &Resources,
+) -> Result<(), String> {
+    let (Some(enums_config), Some(enum_name)) = (enums_config, enum_name) else {
+        return Ok(());
+    };
+    let Some(values) = resources
+        .read(enums_config)
+        .and_then(|content| enum_values(&content, enum_name))
+    else {
+        return Ok(());
+    };
+    if values.iter().any(|known| known == value) {
+        Ok(())
+    } else {
+        Err(format!(
+            "'{}' is not a value of the enum '{}' in {}",
+            value, enum_name, enums_config
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::config::Config;
+    use crate::fix::unified_diff;
+    use crate::resources::Resources;
+    use crate::rules::{check_rules, fix_schema};
+    use crate::tree::parse_document;
+    use std::io::Cursor;
+
+    fn fix(example: &str) -> String {
+        fix_schema(example, &Config::default(), &Resources::default())
+            .unwrap()
+            .0
+    }
+
+    fn rules(example: &str) -> Vec<String> {
+        check_rules(
+            &parse_document(Cursor::new(example)).unwrap(),
+            &Config::default(),
+            &Resources::default(),
+        )
+        .into_iter()
+        .map(|finding| finding.to_string())
+        .collect()
+    }
+
+    #[test]
+    fn test_fix_deprecated_trie_type() {
+        let example = r#"<schema version="1.6">
+  <!-- integers -->
+  <fieldType name="int"   class="solr.TrieIntField" positionIncrementGap="0"/>
+  <fieldType name="long" class="solr.TrieLongField" docValues="false" />
+</schema>"#;
+        assert_eq!(
+            fix(example),
+            r#"<schema version="1.6">
+  <!-- integers -->
+  <fieldType name="int"   class="solr.IntPointField" positionIncrementGap="0" docValues="true"/>
+  <fieldType name="long" class="solr.LongPointField" docValues="false" />
+</schema>"#
+        );
+        let findings = rules(example);
+        assert!(
+            findings.contains(&"error[deprecated-field-type]: The fieldType 'long' uses the deprecated class 'solr.TrieLongField', use 'LongPointField' with docValues instead. It sets docValues=\"false\", so the LongPointField can't be sorted, faceted or used in function queries efficiently.".to_string()),
+            "{:?}",
+            findings
+        );
+    }
+
+    #[test]
+    fn test_fix_boolean_case_and_class_prefix() {
+        let example = r#"<schema version="1.6">
+  <field name="id" type="string" stored="TruE" indexed='FALSE' />
+  <fieldType name="string" class="org.apache.solr.schema.StrField" />
+  <fieldType name="text" class="solr.TextField"><analyzer class="x" /></fieldType>
+</schema>"#;
+        assert_eq!(
+            fix(example),
+            r#"<schema version="1.6">
+  <field name="id" type="string" stored="true" indexed='false' />
+  <fieldType name="string" class="solr.StrField" />
+  <fieldType name="text" class="solr.TextField"><analyzer class="x" /></fieldType>
+</schema>"#
+        );
+    }
+
+    #[test]
+    fn test_fix_deprecated_type_with_long_prefix() {
+        let example = r#"<schema version="1.6">
+  <fieldType name="string" class="solr.StrField" />
+  <fieldType name="tint" class="org.apache.solr.schema.TrieIntField" />
+</schema>"#;
+        assert_eq!(
+            fix(example),
+            r#"<schema version="1.6">
+  <fieldType name="string" class="solr.StrField" />
+  <fieldType name="tint" class="solr.IntPointField" docValues="true" />
+</schema>"#
+        );
+    }
+
+    #[test]
+    fn test_fix_keeps_consistent_class_prefixes() {
+        let example = r#"<schema version="1.6">
+  <fieldType name="string" class="org.apache.solr.schema.StrField" />
+  <fieldType name="text" class="org.apache.solr.schema.TextField" />
+</schema>"#;
+        assert_eq!(fix(example), example);
+    }
+
+    #[test]
+    fn test_unused_field_types_and_unreachable_analyzers() {
+        let example = r#"<schema version="1.6">
+  <field name="id" type="string" />
+  <field name="location" type="bbox" />
+  <fieldType name="string" class="solr.StrField" />
+  <fieldType name="bbox" class="solr.BBoxField" numberType="pdouble" />
+  <fieldType name="pdouble" class="solr.DoublePointField" docValues="true" />
+  <!-- copied from the default configset -->
+  <fieldType name="tint" class="solr.IntPointField" />
+  <fieldType name="text" class="solr.TextField">
+    <analyzer><tokenizer class="solr.StandardTokenizerFactory" /></analyzer>
+    <analyzer type="index"><tokenizer class="solr.StandardTokenizerFactory" /></analyzer>
+    <analyzer type="query"><tokenizer class="solr.StandardTokenizerFactory" /></analyzer>
+    <analyzer type="query"><tokenizer class="solr.KeywordTokenizerFactory" /></analyzer>
+  </fieldType>
+</schema>"#;
+        assert_eq!(
+            rules(example),
+            vec![
+                "warning[unused-field-type]: The fieldType 'tint' is not used by any field or dynamicField.",
+                "warning[unused-field-type]: The fieldType 'text' is not used by any field or dynamicField.",
+                "warning[unreachable-analyzer]: The untyped analyzer of the fieldType 'text' is never used.",
+                "warning[unreachable-analyzer]: The query analyzer of the fieldType 'text' is never used.",
+            ]
+        );
+        assert_eq!(
+            fix(example),
+            r#"<schema version="1.6">
+  <field name="id" type="string" />
+  <field name="location" type="bbox" />
+  <fieldType name="string" class="solr.StrField" />
+  <fieldType name="bbox" class="solr.BBoxField" numberType="pdouble" />
+  <fieldType name="pdouble" class="solr.DoublePointField" docValues="true" />
+  <!-- copied from the default configset -->
+  <fieldType name="tint" class="solr.IntPointField" />
+  <fieldType name="text" class="solr.TextField">
+    <analyzer type="index"><tokenizer class="solr.StandardTokenizerFactory" /></analyzer>
+    <analyzer type="query"><tokenizer class="solr.StandardTokenizerFactory" /></analyzer>
+  </fieldType>
+</schema>"#
+        );
+    }
+
+    #[test]
+    fn test_naming() {
+        let example = r#"<schema version="1.6">
+  <field name="_version_" type="plong" />
+  <field name="_internal_" type="plong" />
+  <field name="1st" type="plong" />
+  <field name="first-name" type="plong" />
+  <field name="ok_name" type="plong" />
+  <dynamicField name="*_t.en" type="plong" />
+  <dynamicField name="attr_*" type="plong" />
+  <dynamicField name="*" type="plong" />
+  <fieldType name="plong" class="solr.LongPointField" docValues="true" />
+</schema>"#;
+        assert_eq!(
+            rules(example),
+            vec![
+                "warning[reserved-field-name]: The field '_internal_' has leading and trailing underscores, which are reserved for Solr's own fields such as [\"_root_\", \"_version_\", \"_nest_path_\", \"_text_\"].",
+                "warning[naming]: The field name '1st' does not match the pattern '^[A-Za-z_][A-Za-z0-9_]*$'.",
+                "warning[naming]: The field name 'first-name' does not match the pattern '^[A-Za-z_][A-Za-z0-9_]*$'. Hyphens, dots and spaces break function queries.",
+                "warning[naming]: The dynamicField name '*_t.en' does not match the pattern '^[A-Za-z0-9_]+$'. Hyphens, dots and spaces break function queries.",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_naming_with_configured_patterns() {
+        let example = r#"<schema version="1.6">
+  <field name="firstName" type="plong" />
+  <fieldType name="plong" class="solr.LongPointField" />
+</schema>"#;
+        let config: Config = toml::from_str("[naming]\nfield = \"^[a-z_]+$\"\n").unwrap();
+        let findings = check_rules(
+            &parse_document(Cursor::new(example)).unwrap(),
+            &config,
+            &Resources::default(),
+        );
+        assert_eq!(
+            findings[0].to_string(),
+            "warning[naming]: The field name 'firstName' does not match the pattern '^[a-z_]+$'."
+        );
+    }
+
+    #[test]
+    fn test_default_values() {
+        let example = r#"<schema version="1.6">
+  <field name="id" type="string" required="true" default="none" />
+  <field name="count" type="pint" default="1.5" />
+  <field name="created" type="pdate" default="NOW/DAY" />
+  <field name="updated" type="pdate" default="yesterday" />
+  <field name="open" type="boolean" default="yes" />
+  <field name="key" type="uuid" default="NEW" />
+  <dynamicField name="*_f" type="pfloat" default="0.5" />
+  <fieldType name="string" class="solr.StrField" />
+  <fieldType name="pint" class="solr.IntPointField" />
+  <fieldType name="pfloat" class="solr.FloatPointField" />
+  <fieldType name="pdate" class="solr.DatePointField" />
+  <fieldType name="boolean" class="solr.BoolField" />
+  <fieldType name="uuid" class="solr.UUIDField" />
+</schema>"#;
+        assert_eq!(
+            rules(example),
+            vec![
+                "warning[required-with-default]: The field 'id' is required and has a default, so it can never be missing and the requirement is never checked.",
+                "error[invalid-default]: The default of the field 'count' is invalid: '1.5' is not a valid value for IntPointField.",
+                "error[invalid-default]: The default of the field 'updated' is invalid: 'yesterday' is not a valid value for DatePointField.",
+                "error[invalid-default]: The default of the field 'open' is invalid: 'yes' is not a valid value for BoolField.",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_enum_default_value() {
+        let example = r#"<schema version="1.6">
+  <field name="severity" type="severity" default="Urgent" />
+  <field name="priority" type="severity" default="Low" />
+  <fieldType name="severity" class="solr.EnumFieldType" enumsConfig="enumsConfig.xml" enumName="severity" />
+</schema>"#;
+        let resources = Resources::default().with_file(
+            "enumsConfig.xml",
+            r#"<enumsConfig><enum name="severity"><value>Low</value><value>High</value></enum></enumsConfig>"#,
+        );
+        let findings = check_rules(
+            &parse_document(Cursor::new(example)).unwrap(),
+            &Config::default(),
+            &resources,
+        );
+        assert_eq!(
+            findings
+                .iter()
+                .map(|finding| finding.to_string())
+                .collect::<Vec<String>>(),
+            vec!["error[invalid-default]: The default of the field 'severity' is invalid: 'Urgent' is not a value of the enum 'severity' in enumsConfig.xml."]
+        );
+    }
+
+    #[test]
+    fn test_system_fields() {
+        let example = r#"<schema version="1.6">
+  <uniqueKey>id</uniqueKey>
+  <field name="id" type="string" />
+  <field name="_nest_path_" type="string" />
+  <field name="_text_" type="text" />
+  <fieldType name="string" class="solr.StrField" />
+  <fieldType name="text" class="solr.TextField" />
+</schema>"#;
+        assert_eq!(
+            rules(example),
+            vec![
+                "warning[system-field]: The schema has a uniqueKey but no '_version_' field, which the updateLog needs.",
+                "error[system-field]: The field '_nest_path_' must use a NestPathField, but its fieldType 'string' is a StrField.",
+                "error[system-field]: The schema has '_nest_path_' but no '_root_' field, which nested documents need.",
+                "warning[system-field]: The field '_text_' is not multiValued, so copying more than one field into it fails.",
+                "warning[system-field]: No copyField writes into the field '_text_'.",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_version_field_follows_update_log() {
+        let example = r#"<schema version="1.6">
+  <field name="title" type="string" />
+  <fieldType name="string" class="solr.StrField" />
+</schema>"#;
+        let document = parse_document(Cursor::new(example)).unwrap();
+        let findings = |solrconfig: &str| {
+            let resources = Resources::default().with_file("solrconfig.xml", solrconfig);
+            check_rules(&document, &Config::default(), &resources)
+                .iter()
+                .map(|finding| finding.to_string())
+                .collect::<Vec<String>>()
+        };
+        assert_eq!(
+            findings("<config><updateHandler><updateLog /></updateHandler></config>"),
+            vec!["error[system-field]: The solrconfig.xml enables the updateLog, but the schema has no '_version_' field, which it needs."]
+        );
+        assert!(findings("<config><updateHandler /></config>").is_empty());
+    }
+
+    #[test]
+    fn test_version_and_root_fields() {
+        let example = r#"<schema version="1.6">
+  <field name="_version_" type="pint" multiValued="true" />
+  <field name="_root_" type="text" indexed="false" />
+  <fieldType name="pint" class="solr.IntPointField" />
+  <fieldType name="text" class="solr.TextField" />
+</schema>"#;
+        assert_eq!(
+            rules(example),
+            vec![
+                "error[system-field]: The field '_version_' must be a long, but its fieldType 'pint' is a IntPointField.",
+                "error[system-field]: The field '_version_' must not be multiValued.",
+                "error[system-field]: The field '_version_' needs docValues.",
+                "error[system-field]: The field '_root_' must be a string, but its fieldType 'text' is a TextField.",
+                "error[system-field]: The field '_root_' must be indexed to find the children of a document.",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_fix_diff() {
+        let example = "<schema version=\"1.6\">\n  <field name=\"id\" type=\"string\" stored=\"TRUE\" />\n</schema>\n";
+        assert_eq!(
+            unified_diff("schema.xml", example, &fix(example)),
+            "--- schema.xml\n+++ schema.xml\n@@ -1,3 +1,3 @@\n <schema version=\"1.6\">\n-  <field name=\"id\" type=\"string\" stored=\"TRUE\" />\n+  <field name=\"id\" type=\"string\" stored=\"true\" />\n </schema>\n"
+        );
+    }
+}
@@ -4,7 +4,7 @@ use std::str::FromStr;
 use xml::attribute::OwnedAttribute;
 use xml::name::OwnedName;
 
-const SCHEME_FIELDS: [&'static str; 11] = [
+const SCHEME_FIELDS: [&str; 11] = [
     "field",
     "fieldType",
     "dynamicField",
@@ -18,7 +18,7 @@ const SCHEME_FIELDS: [&'static str; 11] = [
     "similarity",
 ];
 
-const OPTIONAL_FIELD_PROPERTIES: [&str; 18] = [
+pub(crate) const OPTIONAL_FIELD_PROPERTIES: [&str; 19] = [
     "indexed",
     "stored",
     "docValues",
@@ -30,16 +30,17 @@ const OPTIONAL_FIELD_PROPERTIES: [&str; 18] = [
     "omitTermFreqAndPosition",
     "omitPositions",
     "termVectors",
-    "termPosition",
+    "termPositions",
     "termOffsets",
     "termPayloads",
+    "storeOffsetsWithPositions",
     "required",
-    "unseDocValuesAsStored",
+    "useDocValuesAsStored",
     "large",
     "default",
 ];
 
-const FIELD_TYPE_CLASSES: [&'static str; 27] = [
+pub(crate) const FIELD_TYPE_CLASSES: [&str; 27] = [
     "BBoxField",
     "BinaryField",
     "BoolField",
@@ -69,7 +70,7 @@ const FIELD_TYPE_CLASSES: [&'static str; 27] = [
     "UUIDField",
 ];
 
-const DEPRECATED_FIELD_TYPES: [&'static str; 8] = [
+pub(crate) const DEPRECATED_FIELD_TYPES: [&str; 8] = [
     "CurrencyField",
     "EnumField",
     "TrieDateField",
@@ -80,7 +81,7 @@ const DEPRECATED_FIELD_TYPES: [&'static str; 8] = [
     "TrieField",
 ];
 
-const FIELD_TYPE_GENERAL_PROPERTIES: [&'static str; 7] = [
+pub(crate) const FIELD_TYPE_GENERAL_PROPERTIES: [&str; 7] = [
     "name",
     "positionIncrementGap",
     "autoGeneratePhraseQueries",
@@ -91,12 +92,12 @@ const FIELD_TYPE_GENERAL_PROPERTIES: [&'static str; 7] = [
 ];
 
 // SOLR-17274: https://issues.apache.org/jira/browse/SOLR-17274
-const PRESERVED_SOLR_NAMES: [&'static str; 3] = ["set", "add", "remove"];
+const PRESERVED_SOLR_NAMES: [&str; 3] = ["set", "add", "remove"];
 
-const SOLR_CONSTANT_TYPE_NAMES: [&'static str; 4] =
+pub(crate) const SOLR_CONSTANT_TYPE_NAMES: [&str; 4] =
     ["_root_", "_version_", "_nest_path_", "_text_"];
 
-const FIELD_TYPE_CLASSES_NAMES: [&'static str; 2] = ["solr.", "org.apache.solr.schema."];
+const FIELD_TYPE_CLASSES_NAMES: [&str; 2] = ["solr.", "org.apache.solr.schema."];
 
 const FIELD_DEFINITIONS: [&str; 2] = ["name", "type"];
 
@@ -129,26 +130,26 @@ pub fn schema_parser(
     types: &mut HashMap<String, String>,
     name: &OwnedName,
     attributes: Vec<OwnedAttribute>,
-) {
+) -> Result<(), String> {
     let local_name = name.local_name.as_str();
     if !SCHEME_FIELDS.contains(&local_name) {
-        panic!("Found unsupported schema field: {}.", &local_name)
+        return Err(format!("Found unsupported schema field: {}.", &local_name));
     }
-    let required_fields: Vec<&str> = FIELD_DEFINITIONS.iter().copied().collect();
+    let required_fields: Vec<&str> = FIELD_DEFINITIONS.to_vec();
     let attribute_names: Vec<&str> = attributes
         .iter()
         .map(|attr| attr.name.local_name.as_str())
         .collect();
 
-    match SolrFields::from_str(&local_name) {
-        Ok(field_enum) => match field_enum {
+    if let Ok(field_enum) = SolrFields::from_str(local_name) {
+        match field_enum {
             SolrFields::Field | SolrFields::DynamicField => {
                 let all_required = check_required_field(&required_fields, attribute_names);
                 if !all_required {
-                    panic!(
+                    return Err(format!(
                         "Found unsupported field key or property for 'field': {:?}.",
                         required_fields,
-                    )
+                    ));
                 }
                 for attribute in &attributes {
                     let field_property = attribute.name.local_name.as_str();
@@ -158,10 +159,10 @@ pub fn schema_parser(
                             "type" => {}
                             "default" => {}
                             _ => {
-                                panic!(
+                                return Err(format!(
                                     "Found some optional fields are incorrectly defined for 'field': {}.",
                                     &field_property
-                                )
+                                ));
                             }
                         }
                     }
@@ -170,7 +171,7 @@ pub fn schema_parser(
                             "default" => {}
                             _ => {
                                 if attribute.value != "true" && attribute.value != "false" {
-                                    panic!(
+                                    return Err(format!(
                                         "Found unsupported value '{}' for {} type in {}={}.",
                                         attribute.value,
                                         field_property,
@@ -180,7 +181,7 @@ pub fn schema_parser(
                                             .find(|n| n.name.local_name == "name")
                                             .unwrap()
                                             .value
-                                    )
+                                    ));
                                 }
                             }
                         }
@@ -189,10 +190,10 @@ pub fn schema_parser(
                         let field_name = &attributes.iter().find(|x| x.name.local_name == "name");
                         let field_name_owned = field_name.unwrap();
                         if types.contains_key(&field_name_owned.value.to_owned()) {
-                            panic!(
+                            return Err(format!(
                                 "Found duplicate types with the same name: '{}'.",
                                 field_name_owned.value
-                            )
+                            ));
                         }
                         types.insert(
                             field_name_owned.value.to_owned(),
@@ -200,24 +201,24 @@ pub fn schema_parser(
                         );
                     }
                 }
-                check_duplicate_field_names(names, &local_name, &attributes);
+                check_duplicate_field_names(names, local_name, &attributes)?;
             }
             SolrFields::CopyField => {
                 let dest = &attributes
                     .iter()
                     .find(|n| n.name.local_name == "dest")
-                    .expect("copyField must have the dest attribute.")
+                    .ok_or("copyField must have the dest attribute.")?
                     .value;
                 let source = &attributes
                     .iter()
                     .find(|n| n.name.local_name == "source")
-                    .expect("copyField must have the source attribute.")
+                    .ok_or("copyField must have the source attribute.")?
                     .value;
                 if dest == source {
-                    panic!(
+                    return Err(format!(
                         "dest: '{}' and source: '{}' cannot share the same value in copyField.",
                         dest, source
-                    )
+                    ));
                 }
                 copy_fields.push(dest.to_string());
                 copy_fields.push(source.to_string());
@@ -227,16 +228,15 @@ pub fn schema_parser(
                     .iter()
                     .filter(|e| e.name.local_name == "class")
                     .any(|e| {
-                        return DEPRECATED_FIELD_TYPES
-                            .contains(&&e.value.split('.').last().unwrap());
+                        DEPRECATED_FIELD_TYPES.contains(&e.value.split('.').next_back().unwrap())
                     });
                 if deprecated_attribute {
-                    panic!("Found deprecated class in the fieldType declaration: {:?}. Please consider changing it with the new equivalent type: https://solr.apache.org/guide/solr/latest/indexing-guide/field-types-included-with-solr.html#deprecated-field-types", &attributes)
+                    return Err(format!("Found deprecated class in the fieldType declaration: {:?}. Please consider changing it with the new equivalent type: https://solr.apache.org/guide/solr/latest/indexing-guide/field-types-included-with-solr.html#deprecated-field-types", &attributes));
                 }
                 // check a class that starts with "org.apache.solr.schema" or "solr" and has support one of FIELD_TYPE_CLASSES
                 let class_attribute: Vec<_> = attributes
                     .iter()
-                    .filter(|e| &e.name.local_name == &"class")
+                    .filter(|e| e.name.local_name == "class")
                     .filter(|e| {
                         FIELD_TYPE_CLASSES_NAMES
                             .iter()
@@ -250,28 +250,28 @@ pub fn schema_parser(
                     .cloned()
                     .collect();
                 if class_attribute.is_empty() {
-                    panic!(
+                    return Err(format!(
                         "Found an undefined class type in the fieldType declaration: {:?}",
                         &attributes
-                    )
+                    ));
                 }
                 let not_any_attribute = attributes
                     .iter()
                     .all(|s| !FIELD_TYPE_GENERAL_PROPERTIES.contains(&s.name.local_name.as_str()));
                 if not_any_attribute {
-                    panic!(
+                    return Err(format!(
                         "Could not find any attributes of the fieldType: {:?}.",
                         FIELD_TYPE_GENERAL_PROPERTIES
-                    );
+                    ));
                 }
-                check_duplicate_field_names(names, &local_name, &attributes);
+                check_duplicate_field_names(names, local_name, &attributes)?;
             }
             SolrFields::Unknown(e) => {
                 println!("skipping field, {:?}", &e)
             }
-        },
-        Err(_) => (),
+        }
     }
+    Ok(())
 }
 
 fn check_required_field(required_fields: &Vec<&str>, attribute_names: Vec<&str>) -> bool {
@@ -284,16 +284,21 @@ fn check_required_field(required_fields: &Vec<&str>, attribute_names: Vec<&str>)
 fn check_duplicate_field_names(
     names: &mut Vec<String>,
     local_name: &str,
-    attributes: &Vec<OwnedAttribute>,
-) {
+    attributes: &[OwnedAttribute],
+) -> Result<(), String> {
     let local_name_option = &attributes.iter().find(|x| x.name.local_name == "name");
-    let name_value = &local_name_option.unwrap().value;
+    let name_value = &local_name_option
+        .ok_or_else(|| format!("{} must have the name attribute.", local_name))?
+        .value;
     let name_with_tag = format!("{}:{}", local_name, name_value.as_str());
     if PRESERVED_SOLR_NAMES.contains(&name_value.as_str()) {
-        panic!("Found the reserved keyword '{name_value}' being used in '{local_name}'.")
+        return Err(format!(
+            "Found the reserved keyword '{name_value}' being used in '{local_name}'."
+        ));
     }
     if names.contains(&name_with_tag) && !SOLR_CONSTANT_TYPE_NAMES.contains(&name_value.as_str()) {
-        panic!("Found duplicate field names '{}'.", name_value)
+        return Err(format!("Found duplicate field names '{}'.", name_value));
     }
     names.push(name_with_tag);
+    Ok(())
 }
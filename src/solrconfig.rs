@@ -0,0 +1,650 @@
+use std::sync::LazyLock;
+
+use regex::Regex;
+
+use crate::config::{Config, Profile};
+use crate::model::{Field, FieldType, Schema};
+use crate::resources::Resources;
+use crate::rules::{Finding, Severity};
+use crate::tree::{parse_document, Element};
+
+// the parameter lists of a handler; invariants override, appends add to the request parameters
+const PARAMETER_SECTIONS: [&str; 3] = ["defaults", "appends", "invariants"];
+
+// the `field:` prefixes of a standard query, skipping phrases and local params
+static QUERY_FIELD: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#""(?:[^"\\]|\\.)*"|(?:^|[\s(+\-!]|\{![^}]*\})([A-Za-z_][\w.\-]*):|\{![^}]*\}"#)
+        .expect("the pattern is valid")
+});
+
+// the pseudo fields of the standard query parser
+const MAGIC_FIELDS: [&str; 2] = ["_query_", "_val_"];
+
+/// A parameter that a request handler or initParams sets, e.g. `qf` in `defaults`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Parameter {
+    pub section: String,
+    pub name: String,
+    pub value: String,
+}
+
+/// A `requestHandler`, or the `initParams` for some paths, with the parameters it sets.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RequestHandler {
+    /// `requestHandler` or `initParams`.
+    pub kind: String,
+    /// The name of a requestHandler, or the paths of an initParams.
+    pub name: String,
+    pub class: Option<String>,
+    pub parameters: Vec<Parameter>,
+}
+
+/// An update processor of a chain, with its parameters.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UpdateProcessor {
+    pub class: String,
+    pub element: Element,
+}
+
+impl UpdateProcessor {
+    /// The factory class without its package, e.g. `UUIDUpdateProcessorFactory`.
+    pub fn factory(&self) -> &str {
+        self.class.rsplit('.').next().unwrap_or(&self.class)
+    }
+
+    /// The values of a `str` parameter or of each `str` of an `arr` parameter.
+    pub fn parameter(&self, name: &str) -> Vec<String> {
+        self.element
+            .elements()
+            .filter(|child| child.attribute("name") == Some(name))
+            .flat_map(|child| match child.local_name() {
+                "arr" => child.elements().map(Element::text).collect(),
+                "str" => vec![child.text()],
+                _ => Vec::new(),
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct UpdateChain {
+    pub name: String,
+    /// Whether updates go through the chain unless they ask for another one.
+    pub default: bool,
+    pub processors: Vec<UpdateProcessor>,
+}
+
+/// The parts of solrconfig.xml that refer to the schema.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SolrConfig {
+    pub handlers: Vec<RequestHandler>,
+    pub chains: Vec<UpdateChain>,
+    /// Whether the updateHandler keeps an updateLog, which needs the `_version_` field.
+    pub update_log: bool,
+}
+
+impl SolrConfig {
+    /// Reads the solrconfig.xml of the configset, or `None` if there is none or it is malformed.
+    pub fn load(resources: &Resources) -> Option<SolrConfig> {
+        let document = parse_document(resources.read("solrconfig.xml")?.as_bytes()).ok()?;
+        let handlers = document
+            .root
+            .elements()
+            .filter_map(|element| match element.local_name() {
+                "requestHandler" => Some(request_handler(element, "name")),
+                "initParams" => Some(request_handler(element, "path")),
+                _ => None,
+            })
+            .collect();
+        // processors defined on their own are referred to by name from the chains
+        let named: Vec<(&str, UpdateProcessor)> = document
+            .root
+            .elements()
+            .filter(|element| element.local_name() == "updateProcessor")
+            .map(|element| {
+                (
+                    element.attribute("name").unwrap_or_default(),
+                    update_processor(element),
+                )
+            })
+            .collect();
+        let chains = document
+            .root
+            .elements()
+            .filter(|element| element.local_name() == "updateRequestProcessorChain")
+            .map(|element| {
+                let mut processors: Vec<UpdateProcessor> = element
+                    .attribute("processor")
+                    .unwrap_or_default()
+                    .split(',')
+                    .filter_map(|name| {
+                        named
+                            .iter()
+                            .find(|(named, _)| *named == name.trim())
+                            .map(|(_, processor)| processor.clone())
+                    })
+                    .collect();
+                processors.extend(
+                    element
+                        .elements()
+                        .filter(|child| child.local_name() == "processor")
+                        .map(update_processor),
+                );
+                UpdateChain {
+                    name: element.attribute("name").unwrap_or_default().to_string(),
+                    default: property_value(element.attribute("default").unwrap_or_default())
+                        == "true",
+                    processors,
+                }
+            })
+            .collect();
+        let update_log = document
+            .root
+            .elements()
+            .filter(|element| element.local_name() == "updateHandler")
+            .flat_map(|element| element.elements())
+            .any(|element| element.local_name() == "updateLog");
+        Some(SolrConfig {
+            handlers,
+            chains,
+            update_log,
+        })
+    }
+
+    /// Whether updates can go through the chain: it is the default or a handler selects it.
+    pub fn is_enabled(&self, chain: &UpdateChain) -> bool {
+        chain.default
+            || self.handlers.iter().any(|handler| {
+                handler.parameters.iter().any(|parameter| {
+                    parameter.name == "update.chain"
+                        && property_value(&parameter.value) == chain.name
+                })
+            })
+    }
+}
+
+fn update_processor(element: &Element) -> UpdateProcessor {
+    UpdateProcessor {
+        class: element.attribute("class").unwrap_or_default().to_string(),
+        element: element.clone(),
+    }
+}
+
+// a `${name:default}` property reference is read with its default value
+fn property_value(value: &str) -> &str {
+    match value
+        .trim()
+        .strip_prefix("${")
+        .and_then(|value| value.strip_suffix('}'))
+    {
+        Some(property) => property.split_once(':').map_or("", |(_, default)| default),
+        None => value.trim(),
+    }
+}
+
+fn request_handler(element: &Element, name_attribute: &str) -> RequestHandler {
+    let mut parameters = Vec::new();
+    for section in element.elements().filter(|child| {
+        child.local_name() == "lst"
+            && PARAMETER_SECTIONS.contains(&child.attribute("name").unwrap_or_default())
+    }) {
+        let section_name = section.attribute("name").unwrap_or_default();
+        for parameter in section.elements() {
+            let name = parameter.attribute("name").unwrap_or_default();
+            // an `arr` holds several values of the same parameter
+            let values = match parameter.local_name() {
+                "arr" => parameter.elements().map(Element::text).collect(),
+                _ => vec![parameter.text()],
+            };
+            parameters.extend(values.into_iter().map(|value| Parameter {
+                section: section_name.to_string(),
+                name: name.to_string(),
+                value,
+            }));
+        }
+    }
+    RequestHandler {
+        kind: element.local_name().to_string(),
+        name: element
+            .attribute(name_attribute)
+            .unwrap_or_default()
+            .to_string(),
+        class: element.attribute("class").map(str::to_string),
+        parameters,
+    }
+}
+
+/// What a parameter does with the fields it names, which decides the properties they need.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FieldUse {
+    Query,
+    Return,
+    Sort,
+    Facet,
+    Highlight,
+}
+
+/// The field names a parameter value refers to, leaving out functions, globs, pseudo fields
+/// and parameter references.
+pub fn field_references(parameter: &str, value: &str) -> Vec<(String, FieldUse)> {
+    let (names, field_use): (Vec<&str>, FieldUse) = match parameter {
+        "df" | "spellcheck.field" => (vec![value.trim()], FieldUse::Query),
+        "q" | "fq" => (
+            QUERY_FIELD
+                .captures_iter(value)
+                .filter_map(|captures| Some(captures.get(1)?.as_str()))
+                .filter(|name| !MAGIC_FIELDS.contains(name))
+                .collect(),
+            FieldUse::Query,
+        ),
+        "qf" | "pf" | "pf2" | "pf3" => (
+            value
+                .split_whitespace()
+                .map(|name| name.split(['^', '~']).next().unwrap_or_default())
+                .collect(),
+            FieldUse::Query,
+        ),
+        "fl" => (
+            split_list(value, true)
+                .into_iter()
+                .map(|name| name.rsplit(':').next().unwrap_or_default())
+                .collect(),
+            FieldUse::Return,
+        ),
+        "sort" => (
+            split_list(value, false)
+                .into_iter()
+                .filter_map(|clause| clause.split_whitespace().next())
+                .collect(),
+            FieldUse::Sort,
+        ),
+        "facet.field" => (
+            vec![value.rsplit('}').next().unwrap_or_default().trim()],
+            FieldUse::Facet,
+        ),
+        "hl.fl" => (split_list(value, true), FieldUse::Highlight),
+        _ => return Vec::new(),
+    };
+    names
+        .into_iter()
+        .map(str::trim)
+        .filter(|name| !name.is_empty() && *name != "score")
+        .filter(|name| !name.contains(['*', '(', '[', '$', '{', '?']))
+        .map(|name| (name.to_string(), field_use))
+        .collect()
+}
+
+// splits at commas, and spaces if asked to, that are not within the arguments of a function
+fn split_list(value: &str, at_spaces: bool) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut depth = 0;
+    let mut start = 0;
+    for (i, c) in value.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            ',' if depth == 0 => {
+                parts.push(&value[start..i]);
+                start = i + 1;
+            }
+            ' ' if depth == 0 && at_spaces => {
+                parts.push(&value[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.push(&value[start..]);
+    parts
+}
+
+/// The property a field is missing for the use, or `None` if it supports it.
+pub fn missing_capability(
+    schema: &Schema,
+    field: &Field,
+    field_use: FieldUse,
+) -> Option<&'static str> {
+    let property = |name: &str| schema.property(field, name);
+    let doc_values = property("docValues");
+    match field_use {
+        FieldUse::Query if !property("indexed") && !doc_values => {
+            Some("indexed=\"true\" or docValues=\"true\"")
+        }
+        FieldUse::Return
+            if !(property("stored") || doc_values && property("useDocValuesAsStored")) =>
+        {
+            Some("stored=\"true\" or docValues=\"true\" with useDocValuesAsStored")
+        }
+        FieldUse::Sort | FieldUse::Facet
+            if !(doc_values || property("indexed") && property("uninvertible")) =>
+        {
+            Some("docValues=\"true\", or indexed=\"true\" and uninvertible=\"true\"")
+        }
+        FieldUse::Highlight if !property("stored") => Some("stored=\"true\""),
+        _ => None,
+    }
+}
+
+/// Resolves the fields the request handlers of solrconfig.xml refer to and checks they
+/// support what the handler does with them.
+pub fn check_request_handlers(schema: &Schema, resources: &Resources) -> Vec<Finding> {
+    let Some(solrconfig) = SolrConfig::load(resources) else {
+        return Vec::new();
+    };
+    let mut findings = Vec::new();
+    for handler in &solrconfig.handlers {
+        for parameter in &handler.parameters {
+            for (name, field_use) in field_references(&parameter.name, &parameter.value) {
+                let context = format!(
+                    "The {} '{}' sets {} in {}",
+                    handler.kind, handler.name, parameter.name, parameter.section
+                );
+                let Some(field) = schema.resolve_field(&name) else {
+                    findings.push(Finding {
+                        rule: "request-handler",
+                        severity: Severity::Error,
+                        message: format!(
+                            "{} to '{}', which is not a field and matches no dynamicField.",
+                            context, name
+                        ),
+                        fixes: Vec::new(),
+                    });
+                    continue;
+                };
+                if let Some(missing) = missing_capability(schema, field, field_use) {
+                    findings.push(Finding {
+                        rule: "request-handler",
+                        severity: Severity::Warning,
+                        message: format!(
+                            "{} to '{}', which needs {} for that.",
+                            context, name, missing
+                        ),
+                        fixes: Vec::new(),
+                    });
+                }
+            }
+        }
+    }
+    findings
+}
+
+// the fields each update processor writes to or reads from, with the classes a target may
+// have; an empty list accepts any class
+const UPDATE_PROCESSOR_FIELDS: [(&str, &str, &[&str]); 8] = [
+    (
+        "UUIDUpdateProcessorFactory",
+        "fieldName",
+        &["UUIDField", "StrField"],
+    ),
+    (
+        "TimestampUpdateProcessorFactory",
+        "fieldName",
+        &["DatePointField", "DateRangeField"],
+    ),
+    ("SignatureUpdateProcessorFactory", "signatureField", &[]),
+    ("SignatureUpdateProcessorFactory", "fields", &[]),
+    ("CloneFieldUpdateProcessorFactory", "source", &[]),
+    ("CloneFieldUpdateProcessorFactory", "dest", &[]),
+    (
+        "DocExpirationUpdateProcessorFactory",
+        "expirationFieldName",
+        &["DatePointField", "DateRangeField"],
+    ),
+    ("DocExpirationUpdateProcessorFactory", "ttlFieldName", &[]),
+];
+
+const ADD_SCHEMA_FIELDS_FACTORY: &str = "AddSchemaFieldsUpdateProcessorFactory";
+
+/// Resolves the fields the update processors of solrconfig.xml write to and read from
+/// and checks the class of the fields they fill in.
+pub fn check_update_processors(
+    schema: &Schema,
+    config: &Config,
+    resources: &Resources,
+) -> Vec<Finding> {
+    let Some(solrconfig) = SolrConfig::load(resources) else {
+        return Vec::new();
+    };
+    let mut findings = Vec::new();
+    let mut report = |severity: Severity, message: String| {
+        findings.push(Finding {
+            rule: "update-processor",
+            severity,
+            message,
+            fixes: Vec::new(),
+        })
+    };
+    for chain in &solrconfig.chains {
+        for processor in &chain.processors {
+            let context = format!(
+                "The {} of the updateRequestProcessorChain '{}'",
+                processor.factory(),
+                chain.name
+            );
+            let mut references: Vec<(&str, String, &[&str])> = Vec::new();
+            for (factory, parameter, classes) in UPDATE_PROCESSOR_FIELDS {
+                if factory != processor.factory() {
+                    continue;
+                }
+                let mut values = processor.parameter(parameter);
+                // the UUID goes into the uniqueKey unless another field is named
+                if values.is_empty() && factory == "UUIDUpdateProcessorFactory" {
+                    values.extend(schema.unique_key.clone());
+                }
+                for value in values {
+                    references.extend(
+                        value
+                            .split(',')
+                            .map(str::trim)
+                            .filter(|name| !name.is_empty())
+                            .map(|name| (parameter, name.to_string(), classes)),
+                    );
+                }
+            }
+            for (parameter, name, classes) in references {
+                let Some(field) = schema.resolve_field(&name) else {
+                    report(
+                        Severity::Error,
+                        format!(
+                            "{} sets {} to '{}', which is not a field and matches no dynamicField.",
+                            context, parameter, name
+                        ),
+                    );
+                    continue;
+                };
+                let class_name = schema
+                    .type_of(field)
+                    .map(FieldType::class_name)
+                    .unwrap_or_default();
+                if !classes.is_empty() && !classes.contains(&class_name) {
+                    report(
+                        Severity::Error,
+                        format!(
+                            "{} sets {} to '{}', which is a {} but must be a {}.",
+                            context,
+                            parameter,
+                            name,
+                            class_name,
+                            classes.join(" or a ")
+                        ),
+                    );
+                }
+            }
+            let adds_fields = processor.factory() == ADD_SCHEMA_FIELDS_FACTORY;
+            if adds_fields && config.profile == Profile::Strict && solrconfig.is_enabled(chain) {
+                report(
+                    Severity::Warning,
+                    format!(
+                        "{} adds a field to the schema for every unknown field of a document; \
+                        disable the chain, e.g. with update.autoCreateFields=false, to keep the schema strict.",
+                        context
+                    ),
+                );
+            }
+        }
+    }
+    findings
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::config::Config;
+    use crate::model::Schema;
+    use crate::resources::Resources;
+    use crate::solrconfig::{
+        check_request_handlers, check_update_processors, field_references, FieldUse,
+    };
+    use crate::tree::parse_document;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_field_references() {
+        let names = |parameter: &str, value: &str| -> Vec<String> {
+            field_references(parameter, value)
+                .into_iter()
+                .map(|(name, _)| name)
+                .collect()
+        };
+        assert_eq!(
+            names("qf", "title^2 body_t^0.5  id"),
+            vec!["title", "body_t", "id"]
+        );
+        assert_eq!(names("pf2", "title~2^3"), vec!["title"]);
+        assert_eq!(
+            names("fl", "id,name:title score [docid] *_s, sum(a,b)"),
+            vec!["id", "title"]
+        );
+        assert_eq!(
+            names("sort", "price asc, score desc, div(a,b) asc"),
+            vec!["price"]
+        );
+        assert_eq!(names("facet.field", "{!key=cat}category"), vec!["category"]);
+        assert_eq!(names("df", "${df:_text_}"), Vec::<String>::new());
+        assert_eq!(
+            names(
+                "q",
+                r#"title:"a:b" AND (-body:x OR +cat:y) url:http\://c {!tag=t}type:book *:*"#
+            ),
+            vec!["title", "body", "cat", "url", "type"]
+        );
+        assert_eq!(names("fq", "_query_:\"{!dismax}x\""), Vec::<String>::new());
+        assert_eq!(field_references("sort", "price desc")[0].1, FieldUse::Sort);
+    }
+
+    #[test]
+    fn test_request_handlers() {
+        let schema = Schema::from_document(
+            &parse_document(Cursor::new(
+                r#"<schema version="1.6">
+  <field name="id" type="string" />
+  <field name="title" type="text" />
+  <field name="body" type="text" stored="false" />
+  <field name="price" type="pfloat" docValues="false" />
+  <dynamicField name="*_s" type="string" />
+  <fieldType name="string" class="solr.StrField" docValues="true" />
+  <fieldType name="text" class="solr.TextField" />
+  <fieldType name="pfloat" class="solr.FloatPointField" indexed="false" />
+</schema>"#,
+            ))
+            .unwrap(),
+        );
+        let resources = Resources::default().with_file(
+            "solrconfig.xml",
+            r#"<config>
+  <initParams path="/update/**,/query">
+    <lst name="defaults"><str name="df">titel</str></lst>
+  </initParams>
+  <requestHandler name="/select" class="solr.SearchHandler">
+    <lst name="defaults">
+      <str name="qf">title^2 body category_s</str>
+      <str name="fl">id,title,body</str>
+      <str name="hl.fl">title body</str>
+    </lst>
+    <lst name="invariants">
+      <arr name="facet.field"><str>category_s</str><str>price</str></arr>
+      <str name="sort">price asc</str>
+    </lst>
+  </requestHandler>
+</config>"#,
+        );
+        let findings: Vec<String> = check_request_handlers(&schema, &resources)
+            .into_iter()
+            .map(|finding| finding.to_string())
+            .collect();
+        assert_eq!(
+            findings,
+            vec![
+                "error[request-handler]: The initParams '/update/**,/query' sets df in defaults to 'titel', which is not a field and matches no dynamicField.",
+                "warning[request-handler]: The requestHandler '/select' sets fl in defaults to 'body', which needs stored=\"true\" or docValues=\"true\" with useDocValuesAsStored for that.",
+                "warning[request-handler]: The requestHandler '/select' sets hl.fl in defaults to 'body', which needs stored=\"true\" for that.",
+                "warning[request-handler]: The requestHandler '/select' sets facet.field in invariants to 'price', which needs docValues=\"true\", or indexed=\"true\" and uninvertible=\"true\" for that.",
+                "warning[request-handler]: The requestHandler '/select' sets sort in invariants to 'price', which needs docValues=\"true\", or indexed=\"true\" and uninvertible=\"true\" for that.",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_update_processors() {
+        let schema = Schema::from_document(
+            &parse_document(Cursor::new(
+                r#"<schema version="1.6">
+  <uniqueKey>id</uniqueKey>
+  <field name="id" type="pint" />
+  <field name="title" type="string" />
+  <field name="created" type="string" />
+  <field name="expire_at" type="pdate" />
+  <field name="signature" type="string" />
+  <fieldType name="string" class="solr.StrField" />
+  <fieldType name="pint" class="solr.IntPointField" />
+  <fieldType name="pdate" class="solr.DatePointField" />
+</schema>"#,
+            ))
+            .unwrap(),
+        );
+        let solrconfig = r#"<config>
+  <updateProcessor class="solr.UUIDUpdateProcessorFactory" name="uuid" />
+  <updateProcessor class="solr.AddSchemaFieldsUpdateProcessorFactory" name="add-schema-fields" />
+  <updateRequestProcessorChain name="add-unknown-fields-to-the-schema" default="${update.autoCreateFields:true}" processor="uuid,add-schema-fields">
+    <processor class="solr.RunUpdateProcessorFactory" />
+  </updateRequestProcessorChain>
+  <updateRequestProcessorChain name="dedupe">
+    <processor class="solr.TimestampUpdateProcessorFactory">
+      <str name="fieldName">created</str>
+    </processor>
+    <processor class="solr.SignatureUpdateProcessorFactory">
+      <str name="signatureField">signature</str>
+      <str name="fields">title,body</str>
+    </processor>
+    <processor class="solr.CloneFieldUpdateProcessorFactory">
+      <arr name="source"><str>title</str></arr>
+      <str name="dest">title_exact</str>
+    </processor>
+    <processor class="solr.DocExpirationUpdateProcessorFactory">
+      <str name="expirationFieldName">expire_at</str>
+    </processor>
+  </updateRequestProcessorChain>
+</config>"#;
+        let resources = Resources::default().with_file("solrconfig.xml", solrconfig);
+        let check = |config: &Config| -> Vec<String> {
+            check_update_processors(&schema, config, &resources)
+                .into_iter()
+                .map(|finding| finding.to_string())
+                .collect()
+        };
+        let expected = vec![
+            "error[update-processor]: The UUIDUpdateProcessorFactory of the updateRequestProcessorChain 'add-unknown-fields-to-the-schema' sets fieldName to 'id', which is a IntPointField but must be a UUIDField or a StrField.",
+            "error[update-processor]: The TimestampUpdateProcessorFactory of the updateRequestProcessorChain 'dedupe' sets fieldName to 'created', which is a StrField but must be a DatePointField or a DateRangeField.",
+            "error[update-processor]: The SignatureUpdateProcessorFactory of the updateRequestProcessorChain 'dedupe' sets fields to 'body', which is not a field and matches no dynamicField.",
+            "error[update-processor]: The CloneFieldUpdateProcessorFactory of the updateRequestProcessorChain 'dedupe' sets dest to 'title_exact', which is not a field and matches no dynamicField.",
+        ];
+        assert_eq!(check(&Config::default()), expected);
+
+        let strict: Config = toml::from_str("profile = \"strict\"\n").unwrap();
+        let findings = check(&strict);
+        assert_eq!(findings.len(), expected.len() + 1);
+        assert_eq!(
+            findings[1],
+            "warning[update-processor]: The AddSchemaFieldsUpdateProcessorFactory of the updateRequestProcessorChain 'add-unknown-fields-to-the-schema' adds a field to the schema for every unknown field of a document; disable the chain, e.g. with update.autoCreateFields=false, to keep the schema strict."
+        );
+    }
+}
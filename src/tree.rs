@@ -0,0 +1,147 @@
+use std::io::{BufReader, Read};
+
+use xml::attribute::OwnedAttribute;
+use xml::common::{Position, TextPosition};
+use xml::name::OwnedName;
+use xml::namespace::{Namespace, NS_NO_PREFIX, NS_XMLNS_PREFIX, NS_XML_PREFIX};
+use xml::reader::{EventReader, ParserConfig, XmlEvent};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Node {
+    Element(Element),
+    Comment(String),
+    Text(String),
+    ProcessingInstruction(String, Option<String>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Element {
+    pub name: OwnedName,
+    pub attributes: Vec<OwnedAttribute>,
+    /// The namespaces the start tag declares, as prefixes and URIs; the prefix is empty for a
+    /// default namespace.
+    pub namespaces: Vec<(String, String)>,
+    pub children: Vec<Node>,
+    /// Where the start tag and the end tag begin in the source; both are equal for `<empty />` elements.
+    pub start: TextPosition,
+    pub end: TextPosition,
+}
+
+impl Element {
+    pub fn local_name(&self) -> &str {
+        self.name.local_name.as_str()
+    }
+
+    /// The name as written in the source, e.g. `xi:include`.
+    pub fn qualified_name(&self) -> String {
+        self.name.borrow().to_repr()
+    }
+
+    pub fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|attr| attr.name.local_name == name)
+            .map(|attr| attr.value.as_str())
+    }
+
+    pub fn elements(&self) -> impl Iterator<Item = &Element> {
+        self.children.iter().filter_map(|node| match node {
+            Node::Element(element) => Some(element),
+            _ => None,
+        })
+    }
+
+    pub fn text(&self) -> String {
+        self.children
+            .iter()
+            .filter_map(|node| match node {
+                Node::Text(text) => Some(text.as_str()),
+                _ => None,
+            })
+            .collect::<String>()
+            .trim()
+            .to_string()
+    }
+}
+
+/// Everything found in a schema file: the nodes around the root element and the root itself.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Document {
+    pub prolog: Vec<Node>,
+    pub root: Element,
+    pub epilog: Vec<Node>,
+}
+
+pub fn parse_document<R: Read>(reader: R) -> Result<Document, xml::reader::Error> {
+    let config = ParserConfig::new()
+        .ignore_comments(false)
+        .whitespace_to_characters(true)
+        .cdata_to_characters(true);
+    let mut parser = EventReader::new_with_config(BufReader::new(reader), config);
+
+    let mut prolog: Vec<Node> = Vec::new();
+    let mut epilog: Vec<Node> = Vec::new();
+    let mut stack: Vec<Element> = Vec::new();
+    // the namespaces in scope of each element of the stack
+    let mut scopes: Vec<Namespace> = Vec::new();
+    let mut root: Option<Element> = None;
+    loop {
+        let event = parser.next()?;
+        let position = parser.position();
+        let node = match event {
+            XmlEvent::StartElement {
+                name,
+                attributes,
+                namespace,
+            } => {
+                let declared = namespace
+                    .iter()
+                    .filter(|(prefix, uri)| {
+                        let inherited = scopes.last().and_then(|scope| scope.get(prefix));
+                        let predefined = [NS_XML_PREFIX, NS_XMLNS_PREFIX].contains(prefix)
+                            || (*prefix == NS_NO_PREFIX && uri.is_empty());
+                        !predefined && inherited != Some(uri)
+                    })
+                    .map(|(prefix, uri)| (prefix.to_string(), uri.to_string()))
+                    .collect();
+                scopes.push(namespace);
+                stack.push(Element {
+                    name,
+                    attributes,
+                    namespaces: declared,
+                    children: Vec::new(),
+                    start: position,
+                    end: position,
+                });
+                continue;
+            }
+            XmlEvent::EndElement { .. } => {
+                let mut element = stack.pop().expect("end element without a start element");
+                scopes.pop();
+                element.end = position;
+                match stack.last_mut() {
+                    Some(parent) => parent.children.push(Node::Element(element)),
+                    None => root = Some(element),
+                }
+                continue;
+            }
+            XmlEvent::Comment(comment) => Node::Comment(comment),
+            XmlEvent::Characters(text) => Node::Text(text),
+            XmlEvent::ProcessingInstruction { name, data } => {
+                Node::ProcessingInstruction(name, data)
+            }
+            XmlEvent::EndDocument => break,
+            _ => continue,
+        };
+        match (stack.last_mut(), &root) {
+            (Some(parent), _) => parent.children.push(node),
+            (None, None) => prolog.push(node),
+            (None, Some(_)) => epilog.push(node),
+        }
+    }
+    Ok(Document {
+        prolog,
+        root: root.expect("a well-formed document always has a root element"),
+        epilog,
+    })
+}
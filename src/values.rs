@@ -0,0 +1,154 @@
+// units accepted by Solr date math, e.g. NOW/DAY+6MONTHS
+const DATE_MATH_UNITS: [&str; 17] = [
+    "YEAR",
+    "YEARS",
+    "MONTH",
+    "MONTHS",
+    "DAY",
+    "DAYS",
+    "DATE",
+    "HOUR",
+    "HOURS",
+    "MINUTE",
+    "MINUTES",
+    "SECOND",
+    "SECONDS",
+    "MILLI",
+    "MILLIS",
+    "MILLISECOND",
+    "MILLISECONDS",
+];
+
+/// Checks that a value can be indexed by a field of the given fieldType class.
+/// Classes without a value syntax of their own accept everything.
+pub fn check_value(class_name: &str, value: &str) -> Result<(), String> {
+    let valid = match class_name {
+        "IntPointField" => value.parse::<i32>().is_ok(),
+        "LongPointField" => value.parse::<i64>().is_ok(),
+        "FloatPointField" | "DoublePointField" => value.parse::<f64>().is_ok(),
+        "DatePointField" => is_date(value),
+        "BoolField" => value == "true" || value == "false",
+        "UUIDField" => value == "NEW" || is_uuid(value),
+        "LatLonPointSpatialField" => is_lat_lon(value),
+        _ => true,
+    };
+    if valid {
+        Ok(())
+    } else {
+        Err(format!(
+            "'{}' is not a valid value for {}",
+            value, class_name
+        ))
+    }
+}
+
+/// An ISO-8601 instant as Solr writes it (`1995-12-31T23:59:59.999Z`) or `NOW`,
+/// optionally followed by date math.
+pub fn is_date(value: &str) -> bool {
+    let math = if let Some(math) = value.strip_prefix("NOW") {
+        math
+    } else {
+        let end = value.find('Z').map_or(value.len(), |i| i + 1);
+        if !is_instant(&value[..end]) {
+            return false;
+        }
+        &value[end..]
+    };
+    is_date_math(math)
+}
+
+fn is_instant(value: &str) -> bool {
+    let Some(value) = value.strip_suffix('Z') else {
+        return false;
+    };
+    let (date, time) = value.split_once('T').unwrap_or((value, ""));
+    let (time, fraction) = time.split_once('.').unwrap_or((time, "0"));
+    let numbers = |part: &str, separator: char, lengths: &[usize]| {
+        let parts: Vec<&str> = part.split(separator).collect();
+        parts.len() == lengths.len()
+            && parts.iter().zip(lengths).all(|(part, length)| {
+                part.len() == *length && part.chars().all(|c| c.is_ascii_digit())
+            })
+    };
+    numbers(date, '-', &[4, 2, 2])
+        && numbers(time, ':', &[2, 2, 2])
+        && !fraction.is_empty()
+        && fraction.len() <= 9
+        && fraction.chars().all(|c| c.is_ascii_digit())
+}
+
+fn is_date_math(mut math: &str) -> bool {
+    while !math.is_empty() {
+        let operator = math.as_bytes()[0];
+        if !matches!(operator, b'+' | b'-' | b'/') {
+            return false;
+        }
+        let rest = &math[1..];
+        let digits = if operator == b'/' {
+            0
+        } else {
+            rest.chars().take_while(char::is_ascii_digit).count()
+        };
+        if operator != b'/' && digits == 0 {
+            return false;
+        }
+        let rest = &rest[digits..];
+        let unit_length = rest.chars().take_while(char::is_ascii_uppercase).count();
+        if !DATE_MATH_UNITS.contains(&&rest[..unit_length]) {
+            return false;
+        }
+        math = &rest[unit_length..];
+    }
+    true
+}
+
+pub fn is_uuid(value: &str) -> bool {
+    value.len() == 36
+        && value.char_indices().all(|(i, c)| match i {
+            8 | 13 | 18 | 23 => c == '-',
+            _ => c.is_ascii_hexdigit(),
+        })
+}
+
+fn is_lat_lon(value: &str) -> bool {
+    let Some((lat, lon)) = value.split_once(',') else {
+        return false;
+    };
+    match (lat.trim().parse::<f64>(), lon.trim().parse::<f64>()) {
+        (Ok(lat), Ok(lon)) => (-90.0..=90.0).contains(&lat) && (-180.0..=180.0).contains(&lon),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::values::{check_value, is_date};
+
+    #[test]
+    fn test_dates() {
+        assert!(is_date("1995-12-31T23:59:59Z"));
+        assert!(is_date("1995-12-31T23:59:59.999Z"));
+        assert!(is_date("NOW"));
+        assert!(is_date("NOW/DAY+6MONTHS+3DAYS"));
+        assert!(is_date("1995-12-31T23:59:59Z-1YEAR"));
+        assert!(!is_date("1995-12-31"));
+        assert!(!is_date("1995-12-31T23:59:59"));
+        assert!(!is_date("NOW+DAY"));
+        assert!(!is_date("NOW/FORTNIGHT"));
+    }
+
+    #[test]
+    fn test_values_by_class() {
+        assert!(check_value("IntPointField", "42").is_ok());
+        assert!(check_value("IntPointField", "4.2").is_err());
+        assert!(check_value("LongPointField", "9000000000").is_ok());
+        assert!(check_value("DoublePointField", "4.2").is_ok());
+        assert!(check_value("BoolField", "TRUE").is_err());
+        assert!(check_value("UUIDField", "NEW").is_ok());
+        assert!(check_value("UUIDField", "f81d4fae-7dec-11d0-a765-00a0c91e6bf6").is_ok());
+        assert!(check_value("UUIDField", "f81d4fae").is_err());
+        assert!(check_value("LatLonPointSpatialField", "45.17, -93.87").is_ok());
+        assert!(check_value("LatLonPointSpatialField", "95,10").is_err());
+        assert!(check_value("StrField", "anything").is_ok());
+    }
+}